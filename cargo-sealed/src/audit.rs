@@ -0,0 +1,174 @@
+//! `cargo sealed audit`, reporting the sealed traits and impls of a crate,
+//! and flagging impls implementing a seal that no trait produces.
+
+use std::fmt::Write as _;
+
+use serde::Serialize;
+use serde_json::{json, Value};
+
+use crate::scan::{Crate, Location, SealedImpl, SealedTrait};
+
+/// Rule flagging a `#[sealed]` impl whose seal no `#[sealed]` trait of the
+/// crate produces, e.g. after the trait was renamed.
+pub const UNKNOWN_SEAL: &str = "unknown-seal";
+
+/// Issue found by an audit.
+#[derive(Debug, Clone, Serialize)]
+pub struct Finding {
+    /// Identifier of the violated rule, e.g. [`UNKNOWN_SEAL`].
+    pub rule: &'static str,
+
+    /// Description of the issue.
+    pub message: String,
+
+    /// Where the issue is.
+    pub location: Location,
+}
+
+/// Result of an audit.
+#[derive(Debug, Serialize)]
+pub struct Report {
+    /// Sealed traits of the crate.
+    pub traits: Vec<SealedTrait>,
+
+    /// Sealed impls of the crate.
+    pub impls: Vec<SealedImpl>,
+
+    /// Issues found.
+    pub findings: Vec<Finding>,
+}
+
+/// Audits the scanned `krate`.
+pub fn audit(krate: Crate) -> Report {
+    let findings = krate
+        .impls
+        .iter()
+        .filter(|item_impl| item_impl.exposure.is_none())
+        .filter(|item_impl| {
+            !krate
+                .traits
+                .iter()
+                .flat_map(|t| t.seal.iter().chain(&t.seal_alias))
+                .any(|seal| seal == &item_impl.seal)
+        })
+        .map(|item_impl| Finding {
+            rule: UNKNOWN_SEAL,
+            message: format!(
+                "`#[sealed]` impl of `{}` for `{}` implements `{}::Sealed`, \
+                 which no `#[sealed]` trait of the crate produces",
+                item_impl.trait_path, item_impl.self_type, item_impl.seal,
+            ),
+            location: item_impl.location.clone(),
+        })
+        .collect();
+
+    Report {
+        traits: krate.traits,
+        impls: krate.impls,
+        findings,
+    }
+}
+
+impl Report {
+    /// Formats the report for humans.
+    pub fn to_text(&self) -> String {
+        let mut out = String::new();
+
+        writeln!(out, "sealed traits ({}):", self.traits.len()).unwrap();
+        for t in &self.traits {
+            writeln!(out, "  {} ({})", t.path, t.location).unwrap();
+            match (&t.seal, &t.sealed_by) {
+                (Some(seal), _) => writeln!(out, "    seal: {} ({})", seal, t.seal_visibility),
+                (None, Some(by)) => writeln!(out, "    seal: shared with `{}`", by),
+                (None, None) => Ok(()),
+            }
+            .unwrap();
+            if let Some(alias) = &t.seal_alias {
+                writeln!(out, "    seal alias: {}", alias).unwrap();
+            }
+            if let Some(cfg) = &t.unsealed_if {
+                writeln!(out, "    unsealed if: {}", cfg).unwrap();
+            }
+        }
+
+        writeln!(out, "\nsealed impls ({}):", self.impls.len()).unwrap();
+        for i in &self.impls {
+            writeln!(
+                out,
+                "  {} for {} in {} ({})",
+                i.trait_path, i.self_type, i.module, i.location,
+            )
+            .unwrap();
+            match &i.exposure {
+                Some(exposure) => writeln!(out, "    seal: {} ({})", i.seal, exposure),
+                None => writeln!(out, "    seal: {}", i.seal),
+            }
+            .unwrap();
+        }
+
+        writeln!(out, "\nfindings ({}):", self.findings.len()).unwrap();
+        for f in &self.findings {
+            writeln!(out, "  {}: [{}] {}", f.location, f.rule, f.message).unwrap();
+        }
+        out
+    }
+
+    /// Formats the report as JSON.
+    pub fn to_json(&self) -> Value {
+        serde_json::to_value(self).unwrap()
+    }
+
+    /// Formats the findings of the report as a SARIF 2.1.0 log, for review
+    /// bots.
+    pub fn to_sarif(&self) -> Value {
+        sarif(
+            &[(
+                UNKNOWN_SEAL,
+                "A `#[sealed]` impl implements a seal that no `#[sealed]` trait produces.",
+            )],
+            &self.findings,
+        )
+    }
+}
+
+/// Builds a SARIF 2.1.0 log of `findings`, violating `rules` given with their
+/// descriptions.
+pub(crate) fn sarif(rules: &[(&str, &str)], findings: &[Finding]) -> Value {
+    let rules = rules
+        .iter()
+        .map(|(id, description)| json!({ "id": id, "shortDescription": { "text": description } }))
+        .collect::<Vec<_>>();
+    let results = findings
+        .iter()
+        .map(|f| {
+            json!({
+                "ruleId": f.rule,
+                "level": "error",
+                "message": { "text": f.message },
+                "locations": [{
+                    "physicalLocation": {
+                        "artifactLocation": {
+                            "uri": f.location.file.to_string_lossy().replace('\\', "/"),
+                        },
+                        "region": { "startLine": f.location.line },
+                    },
+                }],
+            })
+        })
+        .collect::<Vec<_>>();
+    json!({
+        "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "cargo-sealed",
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "informationUri": "https://github.com/jmg-duarte/sealed-rs",
+                    "rules": rules,
+                },
+            },
+            "results": results,
+        }],
+    })
+}
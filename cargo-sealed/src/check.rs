@@ -0,0 +1,78 @@
+//! `cargo sealed check`, enforcing that every public trait is either sealed
+//! or deliberately open, so leaving a trait implementable downstream is never
+//! an accident.
+
+use std::path::Path;
+
+use serde_json::Value;
+
+use crate::{
+    audit::{sarif, Finding},
+    scan::{self, Crate},
+};
+
+/// Rule flagging a public trait with neither `#[sealed]` nor
+/// `#[sealed::open]`.
+pub const UNMARKED_TRAIT: &str = "unmarked-trait";
+
+/// Checks the scanned `krate`, returning a finding per public trait with
+/// neither `#[sealed]` nor `#[sealed::open]`.
+pub fn check(krate: &Crate) -> Vec<Finding> {
+    krate
+        .open_traits
+        .iter()
+        .filter(|t| !t.marked)
+        .map(|t| Finding {
+            rule: UNMARKED_TRAIT,
+            message: format!(
+                "public trait `{}` is neither `#[sealed]` nor `#[sealed::open]`",
+                t.path,
+            ),
+            location: t.location.clone(),
+        })
+        .collect()
+}
+
+/// Formats `findings` of a check as a SARIF 2.1.0 log, for review bots.
+pub fn to_sarif(findings: &[Finding]) -> Value {
+    sarif(
+        &[(
+            UNMARKED_TRAIT,
+            "A public trait is neither `#[sealed]` nor `#[sealed::open]`.",
+        )],
+        findings,
+    )
+}
+
+/// Checks the crate being built, for use in its `build.rs`, failing the build
+/// when a public trait has neither `#[sealed]` nor `#[sealed::open]`.
+///
+/// ```rust,ignore
+/// // build.rs, with `cargo-sealed` in the `[build-dependencies]`
+/// fn main() {
+///     cargo_sealed::check::build();
+/// }
+/// ```
+///
+/// # Panics
+///
+/// If the crate fails to be scanned or the check fails.
+pub fn build() {
+    let root = std::env::var_os("CARGO_MANIFEST_DIR").expect("`CARGO_MANIFEST_DIR` is not set");
+    let root = Path::new(&root);
+    println!("cargo:rerun-if-changed={}", root.join("src").display());
+
+    let krate = scan::scan(root).unwrap_or_else(|e| panic!("{}", e));
+    let findings = check(&krate);
+    if !findings.is_empty() {
+        let list = findings
+            .iter()
+            .map(|f| format!("  {}: {}", f.location, f.message))
+            .collect::<Vec<_>>()
+            .join("\n");
+        panic!(
+            "public traits must be `#[sealed]` or `#[sealed::open]`:\n{}",
+            list
+        );
+    }
+}
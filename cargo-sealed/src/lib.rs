@@ -0,0 +1,37 @@
+//! # `cargo-sealed`
+//!
+//! Cargo subcommand auditing the [`#[sealed]`](https://docs.rs/sealed) traits
+//! of a crate. The sources are scanned with `syn`, without compiling nor
+//! network access, and seals are named with the rules of [`sealed_core`].
+//!
+//! ```text
+//! cargo install --path cargo-sealed
+//! cargo sealed audit [--format text|json|sarif] [PATH]
+//! cargo sealed check [--format text|json|sarif] [PATH]
+//! cargo sealed semver [--format text|json] OLD NEW
+//! ```
+//!
+//! `audit` lists every `#[sealed]` trait with its sealing module and the
+//! visibility of that module, and every `#[sealed]` impl with the seal it
+//! implements. An impl implementing a seal that no trait of the crate
+//! produces, e.g. after the trait was renamed, is reported as an
+//! `unknown-seal` finding, failing the command. `--format sarif` writes
+//! the findings as a SARIF 2.1.0 log, for review bots.
+//!
+//! `check` enforces that every public trait is either `#[sealed]` or marked
+//! `#[sealed::open]`, so that leaving a trait implementable downstream is a
+//! deliberate choice. Traits declared `pub` are checked wherever they are,
+//! as re-exports are not followed. The same check may run in the `build.rs`
+//! of the crate with [`check::build`].
+//!
+//! `semver` compares two versions of the crate, e.g. two checkouts made with
+//! `git worktree add`, and reports the traits that became sealed or unsealed
+//! or whose sealing module changed of visibility, the sealed traits added or
+//! removed, and the methods and `#[sealed]` implementors added to or removed
+//! from sealed traits. It fails when a change is breaking: a trait became
+//! sealed, or a sealed trait, one of its methods or implementors was removed.
+
+pub mod audit;
+pub mod check;
+pub mod scan;
+pub mod semver;
@@ -0,0 +1,164 @@
+use std::{
+    path::{Path, PathBuf},
+    process,
+};
+
+use cargo_sealed::{audit, check, scan, semver};
+
+const USAGE: &str = "\
+Audits the #[sealed] traits of a crate
+
+Usage: cargo sealed audit [--format text|json|sarif] [PATH]
+       cargo sealed check [--format text|json|sarif] [PATH]
+       cargo sealed semver [--format text|json] OLD NEW
+
+audit   Lists the sealed traits and impls, failing on unknown seals
+check   Fails on public traits neither #[sealed] nor #[sealed::open]
+semver  Compares the sealed surface of two versions, failing on breaking
+        changes
+
+PATH is the directory of the crate, defaulting to the current one. OLD and
+NEW are the directories of two versions of the crate, e.g. checkouts made
+with `git worktree add`.";
+
+/// Output format of a report.
+enum Format {
+    Text,
+    Json,
+    Sarif,
+}
+
+/// Arguments of a command.
+struct Args {
+    format: Format,
+    paths: Vec<PathBuf>,
+}
+
+fn main() {
+    let mut args = std::env::args().skip(1).peekable();
+    // invoked as `cargo sealed ...`
+    if args.peek().map(String::as_str) == Some("sealed") {
+        args.next();
+    }
+
+    let command = args.next();
+    let code = match (command.as_deref(), parse_args(args)) {
+        (Some("-h" | "--help"), _) | (_, Ok(None)) => {
+            println!("{}", USAGE);
+            0
+        }
+        (Some("audit"), Ok(Some(args))) => run_audit(args),
+        (Some("check"), Ok(Some(args))) => run_check(args),
+        (Some("semver"), Ok(Some(args))) => run_semver(args),
+        (Some("audit" | "check" | "semver"), Err(e)) => {
+            eprintln!("error: {}\n\n{}", e, USAGE);
+            2
+        }
+        _ => {
+            eprintln!("{}", USAGE);
+            2
+        }
+    };
+    process::exit(code);
+}
+
+/// Parses the arguments of a command, returning `None` if help is asked.
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Option<Args>, String> {
+    let mut format = Format::Text;
+    let mut paths = Vec::new();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--format" => {
+                format = match args.next().as_deref() {
+                    Some("text") => Format::Text,
+                    Some("json") => Format::Json,
+                    Some("sarif") => Format::Sarif,
+                    _ => return Err("`--format` expects `text`, `json` or `sarif`".to_owned()),
+                }
+            }
+            "-h" | "--help" => return Ok(None),
+            _ if !arg.starts_with('-') => paths.push(PathBuf::from(arg)),
+            _ => return Err(format!("unexpected argument `{}`", arg)),
+        }
+    }
+    Ok(Some(Args { format, paths }))
+}
+
+/// Scans the crate at the single path of `args`, defaulting to the current
+/// directory, reporting a failure.
+fn scan_crate(args: &Args) -> Option<scan::Crate> {
+    match args.paths.as_slice() {
+        [] => scan_path(Path::new(".")),
+        [path] => scan_path(path),
+        _ => {
+            eprintln!("error: expected a single path\n\n{}", USAGE);
+            None
+        }
+    }
+}
+
+/// Scans the crate at `path`, reporting a failure.
+fn scan_path(path: &Path) -> Option<scan::Crate> {
+    scan::scan(path)
+        .map_err(|e| eprintln!("error: `{}`: {}", path.display(), e))
+        .ok()
+}
+
+/// Runs `cargo sealed audit`, returning the exit code.
+fn run_audit(args: Args) -> i32 {
+    let report = match scan_crate(&args) {
+        Some(krate) => audit::audit(krate),
+        None => return 2,
+    };
+    match args.format {
+        Format::Text => print!("{}", report.to_text()),
+        Format::Json => println!("{:#}", report.to_json()),
+        Format::Sarif => println!("{:#}", report.to_sarif()),
+    }
+    i32::from(!report.findings.is_empty())
+}
+
+/// Runs `cargo sealed check`, returning the exit code.
+fn run_check(args: Args) -> i32 {
+    let findings = match scan_crate(&args) {
+        Some(krate) => check::check(&krate),
+        None => return 2,
+    };
+    match args.format {
+        Format::Text => {
+            for f in &findings {
+                println!("{}: [{}] {}", f.location, f.rule, f.message);
+            }
+            if findings.is_empty() {
+                println!("every public trait is `#[sealed]` or `#[sealed::open]`");
+            }
+        }
+        Format::Json => println!("{:#}", serde_json::to_value(&findings).unwrap()),
+        Format::Sarif => println!("{:#}", check::to_sarif(&findings)),
+    }
+    i32::from(!findings.is_empty())
+}
+
+/// Runs `cargo sealed semver`, returning the exit code.
+fn run_semver(args: Args) -> i32 {
+    if let Format::Sarif = args.format {
+        eprintln!("error: `semver` does not support `--format sarif`");
+        return 2;
+    }
+    let (old, new) = match args.paths.as_slice() {
+        [old, new] => (old, new),
+        _ => {
+            eprintln!("error: expected the OLD and NEW paths\n\n{}", USAGE);
+            return 2;
+        }
+    };
+    let diff = match (scan_path(old), scan_path(new)) {
+        (Some(old), Some(new)) => semver::diff(&old, &new),
+        _ => return 2,
+    };
+    match args.format {
+        Format::Text => print!("{}", diff.to_text()),
+        Format::Json | Format::Sarif => println!("{:#}", diff.to_json()),
+    }
+    i32::from(diff.is_breaking())
+}
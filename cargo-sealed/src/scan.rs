@@ -0,0 +1,484 @@
+//! Analysis of the sources of a crate, finding its `#[sealed]` items without
+//! compiling it.
+//!
+//! The modules are followed from the crate root through their `mod`
+//! declarations, so only the files compiled into the crate are scanned.
+//! Items generated by macros, such as `sealed::typestate!`, are not seen.
+
+use std::{
+    fmt, fs, io,
+    path::{Path, PathBuf},
+};
+
+use quote::ToTokens;
+use sealed_core::{seal_name, seal_path_for, trait_variant_name, ImplOptions, Options};
+use serde::Serialize;
+use syn::ext::IdentExt;
+
+/// Sealed items of a crate.
+#[derive(Debug, Default, Serialize)]
+pub struct Crate {
+    /// Traits with a `#[sealed]` attribute.
+    pub traits: Vec<SealedTrait>,
+
+    /// Impls with a `#[sealed]` attribute.
+    pub impls: Vec<SealedImpl>,
+
+    /// Public traits without a `#[sealed]` attribute.
+    pub open_traits: Vec<OpenTrait>,
+}
+
+/// Trait with a `#[sealed]` attribute.
+#[derive(Debug, Clone, Serialize)]
+pub struct SealedTrait {
+    /// Path of the trait, e.g. `crate::db::Backend`.
+    pub path: String,
+
+    /// Visibility of the trait, either `private` or `pub(...)`.
+    pub visibility: String,
+
+    /// Path of the generated sealing module, e.g. `crate::db::__seal_backend`,
+    /// unless the trait shares the seal of another one with `by = ...`.
+    pub seal: Option<String>,
+
+    /// Path of the module re-exporting the seal under the name of the variant
+    /// created by `#[trait_variant::make(Name: ...)]`, whose impls implement
+    /// it, e.g. `crate::db::__seal_send_backend`.
+    pub seal_alias: Option<String>,
+
+    /// Visibility of the sealing module, either `private` or `pub(...)`.
+    pub seal_visibility: String,
+
+    /// Trait whose seal is shared, given with `by = ...`.
+    pub sealed_by: Option<String>,
+
+    /// `cfg` predicate unsealing the trait, given with `unsealed_if = ...`.
+    pub unsealed_if: Option<String>,
+
+    /// Crates allowed to implement the trait, given with `friends(...)`.
+    pub friends: Vec<String>,
+
+    /// Whether the derive macros of the crate may implement the trait, given
+    /// with `derive`.
+    pub derive: bool,
+
+    /// Whether the trait may be implemented through an `unsafe` trait, given
+    /// with `escape_hatch`.
+    pub escape_hatch: bool,
+
+    /// Names of the methods of the trait.
+    pub methods: Vec<String>,
+
+    /// Names of the methods of the trait without a default body, which every
+    /// impl has to provide.
+    pub required_methods: Vec<String>,
+
+    /// Where the trait is defined.
+    pub location: Location,
+}
+
+/// Public trait without a `#[sealed]` attribute.
+#[derive(Debug, Clone, Serialize)]
+pub struct OpenTrait {
+    /// Path of the trait, e.g. `crate::db::Backend`.
+    pub path: String,
+
+    /// Whether the trait is deliberately open, marked with `#[sealed::open]`.
+    pub marked: bool,
+
+    /// Where the trait is defined.
+    pub location: Location,
+}
+
+/// Impl with a `#[sealed]` attribute.
+#[derive(Debug, Clone, Serialize)]
+pub struct SealedImpl {
+    /// Implemented trait, as written.
+    pub trait_path: String,
+
+    /// Implementing type.
+    pub self_type: String,
+
+    /// Module of the impl, e.g. `crate::db`.
+    pub module: String,
+
+    /// Path of the sealing module implemented, resolved from the module of
+    /// the impl when possible.
+    pub seal: String,
+
+    /// Either `friend`, `derived` or `escape_hatch`, when the trait is in
+    /// another crate and its seal is reached through the hidden path exposed
+    /// to such impls.
+    pub exposure: Option<String>,
+
+    /// Where the impl is written.
+    pub location: Location,
+}
+
+/// Location of an item in the sources.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub struct Location {
+    /// Path of the file, relative to the root of the crate.
+    pub file: PathBuf,
+
+    /// Line of the item, starting at 1.
+    pub line: usize,
+}
+
+impl fmt::Display for Location {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.file.display(), self.line)
+    }
+}
+
+/// Error occurring while scanning a crate.
+#[derive(Debug)]
+pub enum Error {
+    /// No `src/lib.rs` nor `src/main.rs` in the crate.
+    NoCrateRoot(PathBuf),
+
+    /// Reading a source file failed.
+    Io(PathBuf, io::Error),
+
+    /// Parsing a source file, or the arguments of a `#[sealed]` attribute
+    /// in it, failed.
+    Parse(PathBuf, syn::Error),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NoCrateRoot(root) => write!(
+                f,
+                "no `src/lib.rs` nor `src/main.rs` in `{}`",
+                root.display(),
+            ),
+            Self::Io(file, e) => write!(f, "failed to read `{}`: {}", file.display(), e),
+            Self::Parse(file, e) => {
+                let start = e.span().start();
+                write!(
+                    f,
+                    "failed to parse `{}:{}:{}`: {}",
+                    file.display(),
+                    start.line,
+                    start.column + 1,
+                    e,
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::NoCrateRoot(_) => None,
+            Self::Io(_, e) => Some(e),
+            Self::Parse(_, e) => Some(e),
+        }
+    }
+}
+
+/// Scans the crate at `root`, i.e. the directory containing its `Cargo.toml`,
+/// starting from `src/lib.rs` or, for binaries, `src/main.rs`.
+pub fn scan(root: &Path) -> Result<Crate, Error> {
+    let file = ["src/lib.rs", "src/main.rs"]
+        .iter()
+        .map(|file| root.join(file))
+        .find(|file| file.is_file())
+        .ok_or_else(|| Error::NoCrateRoot(root.to_owned()))?;
+
+    let mut scanner = Scanner {
+        root,
+        krate: Crate::default(),
+    };
+    let dir = file.parent().unwrap().to_owned();
+    scanner.scan_file(&file, &dir, &["crate".to_owned()])?;
+    scanner.resolve_impls();
+    Ok(scanner.krate)
+}
+
+/// State of a crate scan.
+struct Scanner<'a> {
+    root: &'a Path,
+    krate: Crate,
+}
+
+impl Scanner<'_> {
+    /// Scans the `file` of the module at `module`, whose child modules are
+    /// looked up in `dir`.
+    fn scan_file(&mut self, file: &Path, dir: &Path, module: &[String]) -> Result<(), Error> {
+        let source = fs::read_to_string(file).map_err(|e| Error::Io(file.to_owned(), e))?;
+        let relative = file.strip_prefix(self.root).unwrap_or(file).to_owned();
+        let ast = syn::parse_file(&source).map_err(|e| Error::Parse(relative.clone(), e))?;
+        self.scan_items(&ast.items, file, &relative, dir, module)
+    }
+
+    /// Scans the `items` of the module at `module`, in `file`.
+    fn scan_items(
+        &mut self,
+        items: &[syn::Item],
+        file: &Path,
+        relative: &Path,
+        dir: &Path,
+        module: &[String],
+    ) -> Result<(), Error> {
+        let location = |span: proc_macro2::Span| Location {
+            file: relative.to_owned(),
+            line: span.start().line,
+        };
+
+        for item in items {
+            match item {
+                syn::Item::Trait(item_trait) => {
+                    if let Some(attr) = sealed_attr(&item_trait.attrs) {
+                        let options: Options =
+                            parse_args(attr).map_err(|e| Error::Parse(relative.to_owned(), e))?;
+                        let sealed_trait = sealed_trait(
+                            item_trait,
+                            &options,
+                            module,
+                            location(item_trait.ident.span()),
+                        )
+                        .map_err(|e| Error::Parse(relative.to_owned(), e))?;
+                        self.krate.traits.push(sealed_trait);
+                    } else if let syn::Visibility::Public(_) = item_trait.vis {
+                        self.krate.open_traits.push(OpenTrait {
+                            path: format!("{}::{}", module.join("::"), item_trait.ident.unraw()),
+                            marked: open_attr(&item_trait.attrs).is_some(),
+                            location: location(item_trait.ident.span()),
+                        });
+                    }
+                }
+                syn::Item::Impl(item_impl) => {
+                    let attr = sealed_attr(&item_impl.attrs);
+                    if let (Some(attr), Some((_, trait_path, _))) = (attr, &item_impl.trait_) {
+                        let options: ImplOptions =
+                            parse_args(attr).map_err(|e| Error::Parse(relative.to_owned(), e))?;
+                        let exposure = if options.is_friend() {
+                            Some("friend".to_owned())
+                        } else if options.is_derived() {
+                            Some("derived".to_owned())
+                        } else if options.is_escape_hatch() {
+                            Some("escape_hatch".to_owned())
+                        } else {
+                            None
+                        };
+                        self.krate.impls.push(SealedImpl {
+                            trait_path: display(trait_path),
+                            self_type: display(&item_impl.self_ty),
+                            module: module.join("::"),
+                            seal: String::new(),
+                            exposure,
+                            location: location(item_impl.impl_token.span),
+                        });
+                    }
+                }
+                syn::Item::Mod(item_mod) => {
+                    let name = item_mod.ident.unraw().to_string();
+                    let mut child = module.to_vec();
+                    child.push(name.clone());
+                    let path_attr = item_mod.attrs.iter().find_map(|attr| match &attr.meta {
+                        syn::Meta::NameValue(meta) if meta.path.is_ident("path") => {
+                            match &meta.value {
+                                syn::Expr::Lit(syn::ExprLit {
+                                    lit: syn::Lit::Str(path),
+                                    ..
+                                }) => Some(path.value()),
+                                _ => None,
+                            }
+                        }
+                        _ => None,
+                    });
+                    match (&item_mod.content, path_attr) {
+                        (Some((_, items)), _) => {
+                            self.scan_items(items, file, relative, &dir.join(&name), &child)?;
+                        }
+                        (None, Some(path)) => {
+                            let file = file.parent().unwrap().join(path);
+                            let dir = file.parent().unwrap().to_owned();
+                            self.scan_file(&file, &dir, &child)?;
+                        }
+                        (None, None) => {
+                            let flat = dir.join(format!("{}.rs", name));
+                            if flat.is_file() {
+                                self.scan_file(&flat, &dir.join(&name), &child)?;
+                            } else {
+                                let nested = dir.join(&name).join("mod.rs");
+                                self.scan_file(&nested, &dir.join(&name), &child)?;
+                            }
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    /// Resolves the seals implemented by the scanned impls, from their
+    /// modules.
+    ///
+    /// Relative paths may refer to imported traits, so they are resolved to a
+    /// trait of their module, or else to the only trait of the crate with the
+    /// same seal name.
+    fn resolve_impls(&mut self) {
+        for item_impl in &mut self.krate.impls {
+            let trait_path: syn::Path = match syn::parse_str(&item_impl.trait_path) {
+                Ok(path) => path,
+                Err(_) => continue,
+            };
+            let mut seal = match seal_path_for(&trait_path) {
+                Ok(seal) => seal
+                    .segments
+                    .iter()
+                    .map(|s| s.ident.unraw().to_string())
+                    .collect::<Vec<_>>(),
+                Err(_) => continue,
+            };
+            // The trait is in another crate, reached through a hidden path.
+            if item_impl.exposure.is_some() {
+                item_impl.seal = seal.join("::");
+                continue;
+            }
+
+            let module = item_impl.module.split("::").collect::<Vec<_>>();
+            item_impl.seal = match seal.first().map(String::as_str) {
+                Some("crate") => seal.join("::"),
+                Some("self") => {
+                    seal.remove(0);
+                    format!("{}::{}", module.join("::"), seal.join("::"))
+                }
+                Some("super") => {
+                    let mut module = module;
+                    while seal.first().map(String::as_str) == Some("super") {
+                        seal.remove(0);
+                        module.pop();
+                    }
+                    format!("{}::{}", module.join("::"), seal.join("::"))
+                }
+                _ => {
+                    let local = format!("{}::{}", module.join("::"), seal.join("::"));
+                    let name = seal.last().unwrap();
+                    let candidates = self
+                        .krate
+                        .traits
+                        .iter()
+                        .flat_map(|t| t.seal.iter().chain(&t.seal_alias))
+                        .filter(|s| s.rsplit("::").next() == Some(name))
+                        .collect::<Vec<_>>();
+                    if candidates.contains(&&local) || candidates.len() != 1 {
+                        local
+                    } else {
+                        candidates[0].clone()
+                    }
+                }
+            };
+        }
+    }
+}
+
+/// Collects the `#[sealed]` trait `item_trait` of the module at `module`.
+fn sealed_trait(
+    item_trait: &syn::ItemTrait,
+    options: &Options,
+    module: &[String],
+    location: Location,
+) -> syn::Result<SealedTrait> {
+    let module = module.join("::");
+    let ident = item_trait.ident.unraw();
+    let sealed_by = options.sealed_by().map(display);
+    let variant = trait_variant_name(&item_trait.attrs)?;
+    let methods = || {
+        item_trait.items.iter().filter_map(|item| match item {
+            syn::TraitItem::Fn(method) => Some(method),
+            _ => None,
+        })
+    };
+    Ok(SealedTrait {
+        path: format!("{}::{}", module, ident),
+        visibility: visibility(&item_trait.vis),
+        seal: sealed_by
+            .is_none()
+            .then(|| format!("{}::{}", module, seal_name(&ident))),
+        seal_alias: variant
+            .filter(|_| sealed_by.is_none())
+            .map(|variant| format!("{}::{}", module, seal_name(variant.unraw()))),
+        seal_visibility: visibility(options.visibility()),
+        sealed_by,
+        unsealed_if: options.unsealed_if().map(display),
+        friends: options.friends().iter().map(syn::LitStr::value).collect(),
+        derive: options.is_derivable(),
+        escape_hatch: options.has_escape_hatch(),
+        methods: methods()
+            .map(|method| method.sig.ident.unraw().to_string())
+            .collect(),
+        required_methods: methods()
+            .filter(|method| method.default.is_none())
+            .map(|method| method.sig.ident.unraw().to_string())
+            .collect(),
+        location,
+    })
+}
+
+/// Formats `visibility` as written in code, or as `private` when omitted.
+fn visibility(visibility: &syn::Visibility) -> String {
+    match visibility {
+        syn::Visibility::Inherited => "private".to_owned(),
+        visibility => display(visibility),
+    }
+}
+
+/// Returns the `#[sealed]` or `#[sealed::sealed]` attribute among `attrs`.
+pub(crate) fn sealed_attr(attrs: &[syn::Attribute]) -> Option<&syn::Attribute> {
+    attrs
+        .iter()
+        .find(|attr| matches!(attr_path(attr).as_slice(), [name] | [_, name] if name == "sealed"))
+}
+
+/// Returns the `#[sealed::open]` attribute among `attrs`, also accepted as
+/// `#[open]` when imported.
+pub(crate) fn open_attr(attrs: &[syn::Attribute]) -> Option<&syn::Attribute> {
+    attrs.iter().find(|attr| match attr_path(attr).as_slice() {
+        [name] => name == "open",
+        [krate, name] => krate == "sealed" && name == "open",
+        _ => false,
+    })
+}
+
+/// Returns the segments of the path of `attr`.
+fn attr_path(attr: &syn::Attribute) -> Vec<String> {
+    attr.path()
+        .segments
+        .iter()
+        .map(|s| s.ident.to_string())
+        .collect()
+}
+
+/// Parses the arguments of `attr`, which has none when given as a path.
+fn parse_args<T: syn::parse::Parse + Default>(attr: &syn::Attribute) -> syn::Result<T> {
+    match &attr.meta {
+        syn::Meta::Path(_) => Ok(T::default()),
+        _ => attr.parse_args(),
+    }
+}
+
+/// Formats `tokens` as written in code, e.g. `Vec<T>` rather than the
+/// `Vec < T >` of their token stream.
+pub(crate) fn display<T: ToTokens>(tokens: T) -> String {
+    let text = tokens.to_token_stream().to_string();
+    let mut out = String::with_capacity(text.len());
+    let chars = text.chars().collect::<Vec<_>>();
+    for (i, &c) in chars.iter().enumerate() {
+        if c == ' ' {
+            let word = |c: Option<&char>| c.map_or(false, |c| c.is_alphanumeric() || *c == '_');
+            let (prev, next) = (chars.get(i.wrapping_sub(1)), chars.get(i + 1));
+            let after_comma = prev == Some(&',');
+            if !(word(prev) && word(next) || after_comma) {
+                continue;
+            }
+        }
+        out.push(c);
+    }
+    out
+}
@@ -0,0 +1,281 @@
+//! `cargo sealed semver`, comparing the sealed surface of two versions of a
+//! crate, e.g. two checkouts of its repository.
+//!
+//! Only `pub` traits are compared, as the others are not part of the API of
+//! the crate. Traits are matched by path, so a moved trait is reported as
+//! removed and added. Only the `#[sealed]` impls of a trait are compared, and not the
+//! plain impls of a trait sharing a seal with `by = ...`.
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt::Write as _,
+};
+
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::scan::Crate;
+
+/// Kind of change of the sealed surface.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ChangeKind {
+    /// A public trait became sealed, breaking its downstream impls.
+    BecameSealed,
+
+    /// A sealed trait became public and open.
+    BecameUnsealed,
+
+    /// A sealed trait was added.
+    TraitAdded,
+
+    /// A sealed trait was removed, or is no longer sealed nor public.
+    TraitRemoved,
+
+    /// The sealing module of a trait changed of visibility, which is only
+    /// visible to the crate.
+    SealVisibilityChanged,
+
+    /// A method was added to a sealed trait, which no downstream impl has to
+    /// provide.
+    MethodAdded,
+
+    /// A method without a default body was added to a sealed trait that
+    /// downstream crates may implement, through `friends(...)`, `derive`,
+    /// `escape_hatch` or `unsealed_if`, breaking their impls.
+    RequiredMethodAdded,
+
+    /// A method was removed from a sealed trait, breaking its downstream
+    /// callers.
+    MethodRemoved,
+
+    /// A type now implements a sealed trait.
+    ImplementorAdded,
+
+    /// A type no longer implements a sealed trait, breaking its downstream
+    /// uses.
+    ImplementorRemoved,
+}
+
+impl ChangeKind {
+    /// Returns whether the change requires a major version bump.
+    pub fn is_breaking(self) -> bool {
+        matches!(
+            self,
+            Self::BecameSealed
+                | Self::TraitRemoved
+                | Self::RequiredMethodAdded
+                | Self::MethodRemoved
+                | Self::ImplementorRemoved
+        )
+    }
+}
+
+/// Change of the sealed surface.
+#[derive(Debug, Clone, Serialize)]
+pub struct Change {
+    /// Kind of the change.
+    pub kind: ChangeKind,
+
+    /// Whether the change requires a major version bump.
+    pub breaking: bool,
+
+    /// Path of the changed trait.
+    pub trait_path: String,
+
+    /// Description of the change.
+    pub message: String,
+}
+
+/// Changes of the sealed surface between two versions of a crate.
+#[derive(Debug, Serialize)]
+pub struct Diff {
+    /// Changes, sorted by trait.
+    pub changes: Vec<Change>,
+}
+
+/// Compares the sealed surface of the scanned crates `old` and `new`.
+pub fn diff(old: &Crate, new: &Crate) -> Diff {
+    let old_sealed = sealed_traits(old);
+    let new_sealed = sealed_traits(new);
+    let old_open = public_open_traits(old);
+    let new_open = public_open_traits(new);
+
+    let mut changes = Vec::new();
+    let mut change = |kind: ChangeKind, trait_path: &str, message: String| {
+        changes.push(Change {
+            kind,
+            breaking: kind.is_breaking(),
+            trait_path: trait_path.to_owned(),
+            message,
+        });
+    };
+
+    for (path, old_trait) in &old_sealed {
+        let new_trait = match new_sealed.get(path) {
+            Some(new_trait) => new_trait,
+            None if new_open.contains(path) => {
+                change(
+                    ChangeKind::BecameUnsealed,
+                    path,
+                    format!("`{}` is no longer sealed", path),
+                );
+                continue;
+            }
+            None => {
+                change(
+                    ChangeKind::TraitRemoved,
+                    path,
+                    format!("sealed trait `{}` was removed", path),
+                );
+                continue;
+            }
+        };
+
+        if old_trait.seal_visibility != new_trait.seal_visibility {
+            change(
+                ChangeKind::SealVisibilityChanged,
+                path,
+                format!(
+                    "seal of `{}` changed from {} to {}",
+                    path, old_trait.seal_visibility, new_trait.seal_visibility,
+                ),
+            );
+        }
+        for method in new_trait.methods.difference(&old_trait.methods) {
+            if old_trait.downstream_impls && new_trait.required_methods.contains(method) {
+                change(
+                    ChangeKind::RequiredMethodAdded,
+                    path,
+                    format!(
+                        "required method `{}` was added to `{}`, which downstream crates \
+                         may implement",
+                        method, path,
+                    ),
+                );
+            } else {
+                change(
+                    ChangeKind::MethodAdded,
+                    path,
+                    format!("method `{}` was added to `{}`", method, path),
+                );
+            }
+        }
+        for method in old_trait.methods.difference(&new_trait.methods) {
+            change(
+                ChangeKind::MethodRemoved,
+                path,
+                format!("method `{}` was removed from `{}`", method, path),
+            );
+        }
+        for ty in new_trait.implementors.difference(&old_trait.implementors) {
+            change(
+                ChangeKind::ImplementorAdded,
+                path,
+                format!("`{}` now implements `{}`", ty, path),
+            );
+        }
+        for ty in old_trait.implementors.difference(&new_trait.implementors) {
+            change(
+                ChangeKind::ImplementorRemoved,
+                path,
+                format!("`{}` no longer implements `{}`", ty, path),
+            );
+        }
+    }
+    for path in new_sealed.keys() {
+        if old_sealed.contains_key(path) {
+            continue;
+        }
+        if old_open.contains(path) {
+            change(
+                ChangeKind::BecameSealed,
+                path,
+                format!("`{}` became sealed", path),
+            );
+        } else {
+            change(
+                ChangeKind::TraitAdded,
+                path,
+                format!("sealed trait `{}` was added", path),
+            );
+        }
+    }
+
+    changes.sort_by(|a, b| (&a.trait_path, a.kind).cmp(&(&b.trait_path, b.kind)));
+    Diff { changes }
+}
+
+/// Sealed trait, as compared between versions.
+struct Surface<'a> {
+    seal_visibility: &'a str,
+    /// Whether downstream crates may implement the trait despite its seal.
+    downstream_impls: bool,
+    methods: BTreeSet<&'a str>,
+    required_methods: BTreeSet<&'a str>,
+    implementors: BTreeSet<&'a str>,
+}
+
+/// Returns the `pub` sealed traits of `krate` by path, with their
+/// implementors.
+fn sealed_traits(krate: &Crate) -> BTreeMap<&str, Surface<'_>> {
+    krate
+        .traits
+        .iter()
+        .filter(|t| t.visibility == "pub")
+        .map(|t| {
+            let implementors = krate
+                .impls
+                .iter()
+                .filter(|i| i.exposure.is_none())
+                .filter(|i| t.seal.iter().chain(&t.seal_alias).any(|s| s == &i.seal))
+                .map(|i| i.self_type.as_str())
+                .collect();
+            let surface = Surface {
+                seal_visibility: &t.seal_visibility,
+                downstream_impls: !t.friends.is_empty()
+                    || t.derive
+                    || t.escape_hatch
+                    || t.unsealed_if.is_some(),
+                methods: t.methods.iter().map(String::as_str).collect(),
+                required_methods: t.required_methods.iter().map(String::as_str).collect(),
+                implementors,
+            };
+            (t.path.as_str(), surface)
+        })
+        .collect()
+}
+
+/// Returns the paths of the public traits of `krate` without `#[sealed]`.
+fn public_open_traits(krate: &Crate) -> BTreeSet<&str> {
+    krate.open_traits.iter().map(|t| t.path.as_str()).collect()
+}
+
+impl Diff {
+    /// Returns whether a change requires a major version bump.
+    pub fn is_breaking(&self) -> bool {
+        self.changes.iter().any(|c| c.breaking)
+    }
+
+    /// Formats the changes for humans, breaking ones first.
+    pub fn to_text(&self) -> String {
+        let mut out = String::new();
+        for (title, breaking) in [("breaking", true), ("non-breaking", false)] {
+            let changes = self
+                .changes
+                .iter()
+                .filter(|c| c.breaking == breaking)
+                .collect::<Vec<_>>();
+            writeln!(out, "{} changes ({}):", title, changes.len()).unwrap();
+            for c in changes {
+                writeln!(out, "  {}", c.message).unwrap();
+            }
+        }
+        out
+    }
+
+    /// Formats the changes as JSON.
+    pub fn to_json(&self) -> Value {
+        serde_json::to_value(self).unwrap()
+    }
+}
@@ -0,0 +1,115 @@
+use std::{path::Path, process::Command};
+
+use cargo_sealed::{
+    audit::{audit, UNKNOWN_SEAL},
+    scan::scan,
+};
+
+const FIXTURE: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/storage");
+
+#[test]
+fn scans_traits_through_modules() {
+    let krate = scan(Path::new(FIXTURE)).unwrap();
+    let traits = krate
+        .traits
+        .iter()
+        .map(|t| {
+            (
+                t.path.as_str(),
+                t.seal.as_deref(),
+                t.seal_visibility.as_str(),
+            )
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        traits,
+        [
+            ("crate::db::Pool", Some("crate::db::__seal_pool"), "private"),
+            (
+                "crate::db::LocalConnection",
+                Some("crate::db::__seal_local_connection"),
+                "private"
+            ),
+            (
+                "crate::Backend",
+                Some("crate::__seal_backend"),
+                "pub(crate)"
+            ),
+            ("crate::Migrate", None, "private"),
+        ],
+    );
+    assert_eq!(krate.traits[0].location.to_string(), "src/db/mod.rs:8");
+    assert_eq!(krate.traits[0].visibility, "pub");
+    assert_eq!(
+        krate.traits[1].seal_alias.as_deref(),
+        Some("crate::db::__seal_connection"),
+    );
+    assert_eq!(krate.traits[3].sealed_by.as_deref(), Some("Backend"));
+}
+
+#[test]
+fn resolves_impl_seals_from_their_module() {
+    let krate = scan(Path::new(FIXTURE)).unwrap();
+    let impls = krate
+        .impls
+        .iter()
+        .map(|i| (i.trait_path.as_str(), i.module.as_str(), i.seal.as_str()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        impls,
+        [
+            (
+                "super::Pool",
+                "crate::db::postgres",
+                "crate::db::__seal_pool"
+            ),
+            (
+                "crate::Backend",
+                "crate::db::postgres",
+                "crate::__seal_backend"
+            ),
+            ("Backend", "crate::db", "crate::__seal_backend"),
+            ("Connection", "crate::db", "crate::db::__seal_connection"),
+            ("Backend", "crate", "crate::__seal_backend"),
+            ("crate::Storage", "crate", "crate::__seal_storage"),
+        ],
+    );
+}
+
+#[test]
+fn flags_unknown_seals() {
+    let report = audit(scan(Path::new(FIXTURE)).unwrap());
+    assert_eq!(report.findings.len(), 1);
+    assert_eq!(report.findings[0].rule, UNKNOWN_SEAL);
+    assert_eq!(report.findings[0].location.to_string(), "src/lib.rs:18");
+
+    let sarif = report.to_sarif();
+    assert_eq!(sarif["version"], "2.1.0");
+    let result = &sarif["runs"][0]["results"][0];
+    assert_eq!(result["ruleId"], UNKNOWN_SEAL);
+    assert_eq!(
+        result["locations"][0]["physicalLocation"]["region"]["startLine"],
+        18
+    );
+}
+
+#[test]
+fn audit_fails_on_findings() {
+    let output = Command::new(env!("CARGO_BIN_EXE_cargo-sealed"))
+        .args(["sealed", "audit", "--format", "json", FIXTURE])
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(1));
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["traits"].as_array().unwrap().len(), 4);
+    assert_eq!(json["findings"][0]["rule"], UNKNOWN_SEAL);
+
+    let output = Command::new(env!("CARGO_BIN_EXE_cargo-sealed"))
+        .args([
+            "audit",
+            concat!(env!("CARGO_MANIFEST_DIR"), "/../demo-core"),
+        ])
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(0));
+}
@@ -0,0 +1,47 @@
+use std::{path::Path, process::Command};
+
+use cargo_sealed::{
+    check::{check, UNMARKED_TRAIT},
+    scan::scan,
+};
+
+const FIXTURE: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/storage");
+
+#[test]
+fn collects_public_open_traits() {
+    let krate = scan(Path::new(FIXTURE)).unwrap();
+    let traits = krate
+        .open_traits
+        .iter()
+        .map(|t| (t.path.as_str(), t.marked))
+        .collect::<Vec<_>>();
+    assert_eq!(traits, [("crate::Hook", true), ("crate::Codec", false)]);
+}
+
+#[test]
+fn flags_unmarked_traits() {
+    let findings = check(&scan(Path::new(FIXTURE)).unwrap());
+    assert_eq!(findings.len(), 1);
+    assert_eq!(findings[0].rule, UNMARKED_TRAIT);
+    assert_eq!(findings[0].location.to_string(), "src/lib.rs:23");
+}
+
+#[test]
+fn check_fails_on_findings() {
+    let output = Command::new(env!("CARGO_BIN_EXE_cargo-sealed"))
+        .args(["sealed", "check", "--format", "sarif", FIXTURE])
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(1));
+    let sarif: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(sarif["runs"][0]["results"][0]["ruleId"], UNMARKED_TRAIT);
+
+    let output = Command::new(env!("CARGO_BIN_EXE_cargo-sealed"))
+        .args([
+            "check",
+            concat!(env!("CARGO_MANIFEST_DIR"), "/../demo-core"),
+        ])
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(0));
+}
@@ -0,0 +1,42 @@
+use sealed::sealed;
+
+#[sealed(pub(crate))]
+pub trait Backend {
+    fn connect(&self);
+
+    fn close(&self) {}
+}
+
+#[sealed::open]
+pub trait Store {}
+
+#[sealed]
+pub trait Codec {}
+
+#[sealed(friends("storage-ext"))]
+pub trait Plugin {
+    fn start(&self);
+
+    fn stop(&self);
+
+    fn pause(&self) {}
+}
+
+pub struct Memory;
+
+#[sealed]
+impl Backend for Memory {
+    fn connect(&self) {}
+}
+
+pub struct Sqlite;
+
+#[sealed]
+impl Backend for Sqlite {
+    fn connect(&self) {}
+}
+
+#[sealed]
+pub(crate) trait Cache {
+    fn get(&self);
+}
@@ -0,0 +1,45 @@
+use sealed::sealed;
+
+#[sealed]
+pub trait Backend {
+    fn connect(&self);
+}
+
+#[sealed]
+pub trait Store {}
+
+pub trait Codec {}
+
+#[sealed]
+pub trait Legacy {}
+
+#[sealed(friends("storage-ext"))]
+pub trait Plugin {
+    fn start(&self);
+}
+
+pub struct Memory;
+
+#[sealed]
+impl Backend for Memory {
+    fn connect(&self) {}
+}
+
+pub struct Postgres;
+
+#[sealed]
+impl Backend for Postgres {
+    fn connect(&self) {}
+}
+
+// neither is part of the API, so changing them is not reported
+
+#[sealed]
+pub(crate) trait Cache {
+    fn get(&self);
+
+    fn clear(&self);
+}
+
+#[sealed]
+trait Index {}
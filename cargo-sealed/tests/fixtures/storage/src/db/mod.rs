@@ -0,0 +1,24 @@
+use sealed::sealed;
+
+use crate::Backend;
+
+mod postgres;
+
+#[sealed(unsealed_if = feature = "unstable")]
+pub trait Pool {}
+
+pub struct Pooled;
+
+#[sealed]
+impl Backend for Pooled {}
+
+#[sealed]
+#[trait_variant::make(Connection: Send)]
+pub trait LocalConnection {
+    async fn close(&self);
+}
+
+#[sealed]
+impl Connection for Pooled {
+    async fn close(&self) {}
+}
@@ -0,0 +1,9 @@
+use sealed::sealed;
+
+pub struct Postgres;
+
+#[sealed]
+impl super::Pool for Postgres {}
+
+#[sealed]
+impl crate::Backend for Postgres {}
@@ -0,0 +1,25 @@
+use sealed::sealed;
+
+mod db;
+
+#[sealed(pub(crate))]
+pub trait Backend {}
+
+#[sealed(by = Backend)]
+pub trait Migrate {}
+
+pub struct Memory;
+
+#[sealed]
+impl Backend for Memory {}
+
+// `Storage` was renamed to `Backend`
+#[sealed]
+impl crate::Storage for Memory {}
+
+#[sealed::open]
+pub trait Hook {}
+
+pub trait Codec {}
+
+pub(crate) trait Internal {}
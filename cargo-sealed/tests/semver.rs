@@ -0,0 +1,69 @@
+use std::{path::Path, process::Command};
+
+use cargo_sealed::{
+    scan::scan,
+    semver::{diff, ChangeKind},
+};
+
+const OLD: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/semver/old");
+const NEW: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/semver/new");
+
+#[test]
+fn reports_sealed_surface_changes() {
+    let diff = diff(
+        &scan(Path::new(OLD)).unwrap(),
+        &scan(Path::new(NEW)).unwrap(),
+    );
+    let changes = diff
+        .changes
+        .iter()
+        .map(|c| (c.trait_path.as_str(), c.kind, c.breaking))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        changes,
+        [
+            ("crate::Backend", ChangeKind::SealVisibilityChanged, false),
+            ("crate::Backend", ChangeKind::MethodAdded, false),
+            ("crate::Backend", ChangeKind::ImplementorAdded, false),
+            ("crate::Backend", ChangeKind::ImplementorRemoved, true),
+            ("crate::Codec", ChangeKind::BecameSealed, true),
+            ("crate::Legacy", ChangeKind::TraitRemoved, true),
+            ("crate::Plugin", ChangeKind::MethodAdded, false),
+            ("crate::Plugin", ChangeKind::RequiredMethodAdded, true),
+            ("crate::Store", ChangeKind::BecameUnsealed, false),
+        ],
+    );
+    assert_eq!(
+        diff.changes[1].message,
+        "method `close` was added to `crate::Backend`"
+    );
+    assert!(diff.is_breaking());
+}
+
+#[test]
+fn same_version_has_no_changes() {
+    let krate = scan(Path::new(NEW)).unwrap();
+    assert!(diff(&krate, &krate).changes.is_empty());
+}
+
+#[test]
+fn semver_fails_on_breaking_changes() {
+    let output = Command::new(env!("CARGO_BIN_EXE_cargo-sealed"))
+        .args(["sealed", "semver", "--format", "json", OLD, NEW])
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(1));
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["changes"][4]["kind"], "became-sealed");
+
+    let output = Command::new(env!("CARGO_BIN_EXE_cargo-sealed"))
+        .args(["semver", NEW, OLD])
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(1));
+    let output = Command::new(env!("CARGO_BIN_EXE_cargo-sealed"))
+        .args(["semver", OLD, OLD])
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(0));
+}
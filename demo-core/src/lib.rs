@@ -0,0 +1,102 @@
+use sealed::sealed;
+
+/// Propulsion of a drone, implemented by the `demo` crate.
+#[sealed(friends("demo"), doctest, mock)]
+pub trait Engine {
+    fn thrust(&self) -> f32;
+}
+
+pub struct Propeller;
+
+#[sealed]
+impl Engine for Propeller {
+    fn thrust(&self) -> f32 {
+        1.0
+    }
+}
+
+/// Sensor of a drone, which other crates are meant to wrap with `impl_sensor!`.
+#[sealed(extend_macro = impl_sensor, doctest)]
+pub trait Sensor {
+    fn read(&self) -> f32;
+}
+
+pub struct Barometer;
+
+#[sealed]
+impl Sensor for Barometer {
+    fn read(&self) -> f32 {
+        1013.25
+    }
+}
+
+/// Pressure read by a [`Sensor`], in hectopascals.
+pub struct Pressure(pub f32);
+
+/// Altimeter of a drone, converting pressures into altitudes.
+#[sealed(doctest)]
+pub trait Altimeter {
+    fn altitude(&self, pressure: Pressure) -> f32;
+}
+
+#[sealed]
+impl Altimeter for Barometer {
+    fn altitude(&self, pressure: Pressure) -> f32 {
+        44330.0 * (1.0 - (pressure.0 / 1013.25).powf(0.1903))
+    }
+}
+
+/// Frame of a drone, fixing its number of rotors.
+#[sealed(implementors(Quad, Hexa))]
+pub trait Frame {
+    fn rotors(&self) -> u8;
+}
+
+pub struct Quad;
+
+#[sealed]
+impl Frame for Quad {
+    fn rotors(&self) -> u8 {
+        4
+    }
+}
+
+pub struct Hexa;
+
+#[sealed]
+impl Frame for Hexa {
+    fn rotors(&self) -> u8 {
+        6
+    }
+}
+
+/// Battery of a drone, whose number of cells makes it unusable as `dyn
+/// Battery`.
+#[sealed(implementors(LiPo, LiIon))]
+pub trait Battery {
+    const CELLS: u8;
+}
+
+pub struct LiPo;
+
+#[sealed]
+impl Battery for LiPo {
+    const CELLS: u8 = 4;
+}
+
+pub struct LiIon;
+
+#[sealed]
+impl Battery for LiIon {
+    const CELLS: u8 = 6;
+}
+
+/// Payload of a drone, which other crates are meant to implement through the
+/// derive macros of this one.
+#[sealed(derive)]
+pub trait Payload {}
+
+pub struct Camera;
+
+#[sealed]
+impl Payload for Camera {}
@@ -0,0 +1,16 @@
+#[test]
+fn traits_are_sealed() {
+    sealed::assert_sealed!(demo_core::Frame);
+    sealed::assert_sealed!(demo_core::Sensor);
+    sealed::assert_sealed!(demo_core::Payload);
+}
+
+#[test]
+fn frame_implementors() {
+    sealed::assert_implementors!(demo_core::Frame: demo_core::Quad, demo_core::Hexa);
+}
+
+#[test]
+fn battery_implementors() {
+    sealed::assert_implementors!(demo_core::Battery: demo_core::LiPo, demo_core::LiIon);
+}
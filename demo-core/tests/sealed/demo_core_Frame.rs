@@ -0,0 +1,8 @@
+// Generated by `sealed::assert_sealed!`, checking that `demo_core::Frame` cannot
+// be implemented outside of its crate.
+
+struct Fresh;
+
+impl demo_core::Frame for Fresh {}
+
+fn main() {}
@@ -0,0 +1,8 @@
+// Generated by `sealed::assert_sealed!`, checking that `demo_core::Payload` cannot
+// be implemented outside of its crate.
+
+struct Fresh;
+
+impl demo_core::Payload for Fresh {}
+
+fn main() {}
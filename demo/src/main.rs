@@ -1,63 +1,42 @@
-use std::marker::PhantomData;
-
-use sealed::sealed;
-
-#[sealed]
-pub trait DroneState {}
+sealed::typestate! {
+    pub struct Drone {
+        x: f32,
+        y: f32,
+    }
 
-pub struct Drone<State>
-where
-    State: DroneState,
-{
-    x: f32,
-    y: f32,
-    state: PhantomData<State>,
+    Idle -> Hovering: take_off,
+    Hovering -> Idle: land,
+    Hovering -> Flying: start_flying,
+    Flying -> Hovering: stop,
 }
 
-pub struct Idle;
-#[sealed]
-impl DroneState for Idle {}
+/// An engine only the `demo` crate may add, as a friend of `demo-core`.
+pub struct Jet;
 
-pub struct Hovering;
-#[sealed]
-impl DroneState for Hovering {}
-
-pub struct Flying;
-#[sealed]
-impl DroneState for Flying {}
-
-impl Drone<Idle> {
-    #[must_use]
-    pub fn new() -> Self {
-        Self {
-            state: PhantomData,
-            x: 0.0,
-            y: 0.0,
-        }
+#[sealed::sealed(friend)]
+impl demo_core::Engine for Jet {
+    fn thrust(&self) -> f32 {
+        10.0
     }
+}
 
-    #[must_use]
-    pub fn take_off(self) -> Drone<Hovering> {
-        Drone::<Hovering>::from(self)
-    }
+/// A sensor wrapping another one, as allowed by `demo-core`.
+pub struct Calibrated {
+    sensor: demo_core::Barometer,
 }
 
+demo_core::impl_sensor!(Calibrated => sensor: demo_core::Barometer);
+
 impl Default for Drone<Idle> {
     fn default() -> Self {
-        Self::new()
+        Self::new(0.0, 0.0)
     }
 }
 
 impl Drone<Hovering> {
-    #[must_use]
-    fn land(self) -> Drone<Idle> {
-        Drone::<Idle>::from(self)
-    }
-
     #[must_use]
     fn move_to(self, x: f32, y: f32) -> Drone<Hovering> {
-        let drone = Drone::<Flying>::from(self);
-        drone.fly(x, y)
+        self.start_flying().fly(x, y)
     }
 }
 
@@ -73,47 +52,7 @@ impl Drone<Flying> {
             self.y = y;
             std::thread::sleep(std::time::Duration::from_millis(10));
         }
-        Drone::<Hovering>::from(self)
-    }
-}
-
-impl From<Drone<Idle>> for Drone<Hovering> {
-    fn from(drone: Drone<Idle>) -> Self {
-        Self {
-            x: drone.x,
-            y: drone.y,
-            state: PhantomData,
-        }
-    }
-}
-
-impl From<Drone<Flying>> for Drone<Hovering> {
-    fn from(drone: Drone<Flying>) -> Self {
-        Self {
-            x: drone.x,
-            y: drone.y,
-            state: PhantomData,
-        }
-    }
-}
-
-impl From<Drone<Hovering>> for Drone<Flying> {
-    fn from(drone: Drone<Hovering>) -> Self {
-        Self {
-            x: drone.x,
-            y: drone.y,
-            state: PhantomData,
-        }
-    }
-}
-
-impl From<Drone<Hovering>> for Drone<Idle> {
-    fn from(drone: Drone<Hovering>) -> Self {
-        Self {
-            x: drone.x,
-            y: drone.y,
-            state: PhantomData,
-        }
+        self.stop()
     }
 }
 
@@ -122,14 +61,14 @@ mod drone_test {
     use super::*;
     #[test]
     fn drone_spawns_idle() {
-        let drone = Drone::<Idle>::new();
+        let drone = Drone::<Idle>::new(0.0, 0.0);
         assert!(drone.x.abs() < f32::EPSILON);
         assert!(drone.y.abs() < f32::EPSILON);
     }
 
     #[test]
     fn drone_takes_off_n_lands() {
-        let drone = Drone::<Idle>::new();
+        let drone = Drone::<Idle>::new(0.0, 0.0);
         let drone = drone.take_off();
         assert!(drone.x.abs() < f32::EPSILON);
         assert!(drone.y.abs() < f32::EPSILON);
@@ -138,14 +77,51 @@ mod drone_test {
 
     #[test]
     fn drone_flies() {
-        let drone = Drone::<Idle>::new().take_off().move_to(-5.0, -5.0).land();
+        let drone = Drone::<Idle>::new(0.0, 0.0)
+            .take_off()
+            .move_to(-5.0, -5.0)
+            .land();
         assert!((drone.x - -5.0).abs() < f32::EPSILON);
         assert!((drone.y - -5.0).abs() < f32::EPSILON);
     }
 
+    #[sealed::test_each(DroneState)]
+    fn drone_state_is_zero_sized<State: DroneState>() {
+        assert_eq!(
+            std::mem::size_of::<Drone<State>>(),
+            2 * std::mem::size_of::<f32>()
+        );
+    }
+
+    #[test]
+    fn friend_crate_implements_engine() {
+        use demo_core::Engine;
+
+        assert!(Jet.thrust() > demo_core::Propeller.thrust());
+    }
+
+    #[test]
+    fn mocked_engine_is_sealed() {
+        use demo_core::Engine;
+
+        let mut engine = demo_core::MockEngine::new();
+        engine.expect_thrust().return_const(2.0);
+        assert!((engine.thrust() - 2.0).abs() < f32::EPSILON);
+    }
+
+    #[test]
+    fn extended_sensor_forwards() {
+        use demo_core::Sensor;
+
+        let calibrated = Calibrated {
+            sensor: demo_core::Barometer,
+        };
+        assert!((calibrated.read() - calibrated.sensor.read()).abs() < f32::EPSILON);
+    }
+
     #[test]
     fn drone_does_not_fly_idle() {
-        let drone = Drone::<Idle>::new();
+        let drone = Drone::<Idle>::new(0.0, 0.0);
         // drone.move_to(10.0, 10.0); // comptime error: "move_to" is not a member of type Idle
         assert!(drone.x.abs() < f32::EPSILON);
         assert!(drone.y.abs() < f32::EPSILON);
@@ -153,5 +129,8 @@ mod drone_test {
 }
 
 fn main() {
-    let _drone = Drone::<Idle>::new().take_off().move_to(-5.0, -5.0).land();
+    let _drone = Drone::<Idle>::new(0.0, 0.0)
+        .take_off()
+        .move_to(-5.0, -5.0)
+        .land();
 }
@@ -0,0 +1,40 @@
+#[sealed::builder]
+#[derive(Debug)]
+pub struct Request {
+    pub url: String,
+    pub method: String,
+    #[builder(default = 30)]
+    pub timeout: u32,
+}
+// expands to:
+// pub struct Request { ... }
+// pub mod request_builder {
+//     pub trait FieldState: __seal_field_state::Sealed {}
+//     pub struct Set;
+//     pub struct Unset;
+// }
+// pub struct RequestBuilder<__Url = Unset, __Method = Unset> { ... }
+// impl Request {
+//     pub fn builder() -> RequestBuilder<Unset, Unset> { ... }
+// }
+// impl<__Method> RequestBuilder<Unset, __Method> {
+//     pub fn url(self, url: String) -> RequestBuilder<Set, __Method> { ... }
+// }
+// impl<__Url> RequestBuilder<__Url, Unset> {
+//     pub fn method(self, method: String) -> RequestBuilder<__Url, Set> { ... }
+// }
+// impl<__Url, __Method> RequestBuilder<__Url, __Method> {
+//     pub fn timeout(self, timeout: u32) -> Self { ... }
+// }
+// impl RequestBuilder<Set, Set> {
+//     pub fn build(self) -> Request { ... }
+// }
+
+fn main() {
+    let request = Request::builder()
+        .method("GET".to_owned())
+        .url("https://crates.io".to_owned())
+        .build();
+    // Request::builder().url("https://crates.io".to_owned()).build(); // compile error: `method` is not set
+    println!("{:?}", request);
+}
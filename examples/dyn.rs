@@ -0,0 +1,37 @@
+use sealed::sealed;
+
+#[sealed(dyn(Clone, PartialEq, Debug))]
+pub trait Shape {
+    fn area(&self) -> u32;
+}
+// expands to:
+// pub trait Shape: __seal_shape::Sealed {
+//     fn area(&self) -> u32;
+// }
+// mod __seal_shape {
+//     pub trait Sealed: DynClone + core::fmt::Debug + DynPartialEq + DynAny {}
+//     pub trait DynClone {
+//         fn __clone_box(&self) -> Box<dyn super::Shape>;
+//     }
+//     impl<X: super::Shape + Clone + 'static> DynClone for X { ... }
+//     ...
+// }
+// impl Clone for Box<dyn Shape> { ... }
+// impl PartialEq for dyn Shape { ... }
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct Square(u32);
+
+#[sealed]
+impl Shape for Square {
+    fn area(&self) -> u32 {
+        self.0 * self.0
+    }
+}
+
+fn main() {
+    let shapes: Vec<Box<dyn Shape>> = vec![Box::new(Square(1)), Box::new(Square(2))];
+    let copies = shapes.clone();
+    assert!(shapes == copies);
+    println!("{:?}", copies);
+}
@@ -0,0 +1,62 @@
+use sealed::sealed;
+
+// `Oracle` stands for a backend behind a disabled feature, which `#[cfg(any())]`
+// never compiles in.
+
+#[sealed(implementors(Postgres = Postgres::default, Sqlite, #[cfg(any())] Oracle))]
+pub trait Backend {
+    fn connect(&self) -> String;
+}
+// expands to:
+// pub trait Backend: __seal_backend::Sealed { ... }
+// mod __seal_backend {
+//     pub trait Sealed: ListedImplementor {}
+//     pub trait ListedImplementor {}
+// }
+// impl __seal_backend::ListedImplementor for Postgres {}
+// impl __seal_backend::ListedImplementor for Sqlite {}
+// #[cfg(any())]
+// impl __seal_backend::ListedImplementor for Oracle {}
+// impl dyn Backend {
+//     pub const IMPLEMENTORS: &'static [&'static str] = &[...];
+//     pub const fn count() -> usize { ... }
+//     pub fn for_each_implementor<F>(f: F) { ... }
+// }
+
+#[derive(Default)]
+pub struct Postgres;
+
+#[sealed]
+impl Backend for Postgres {
+    fn connect(&self) -> String {
+        "postgres://localhost".to_owned()
+    }
+}
+
+pub struct Sqlite;
+
+#[sealed]
+impl Backend for Sqlite {
+    fn connect(&self) -> String {
+        "sqlite::memory:".to_owned()
+    }
+}
+
+#[cfg(any())]
+pub struct Oracle;
+
+#[cfg(any())]
+#[sealed]
+impl Backend for Oracle {
+    fn connect(&self) -> String {
+        "oracle://localhost".to_owned()
+    }
+}
+
+fn main() {
+    println!("{} backends compiled in", <dyn Backend>::count());
+    <dyn Backend>::for_each_implementor(|name, constructor| match constructor {
+        Some(new) => println!("{}: {}", name, new().connect()),
+        None => println!("{}: no default constructor", name),
+    });
+}
@@ -0,0 +1,46 @@
+#[sealed::type_enum]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Color {
+    Red,
+    Green,
+}
+// expands to:
+// pub enum Color { Red, Green }
+// pub trait ColorKind: __seal_color_kind::Sealed {
+//     const VALUE: Color;
+// }
+// pub struct Red;
+// impl ColorKind for Red {
+//     const VALUE: Color = Color::Red;
+// }
+// pub struct Green;
+// impl ColorKind for Green {
+//     const VALUE: Color = Color::Green;
+// }
+// macro_rules! dispatch_color {
+//     ($value:expr, |$kind:ident| $body:expr) => {
+//         match $value {
+//             Color::Red => { type $kind = Red; $body }
+//             Color::Green => { type $kind = Green; $body }
+//         }
+//     };
+// }
+
+pub struct Pixel<C: ColorKind> {
+    pub intensity: u8,
+    pub color: std::marker::PhantomData<C>,
+}
+
+fn describe<C: ColorKind>(intensity: u8) -> String {
+    let pixel = Pixel::<C> {
+        intensity,
+        color: std::marker::PhantomData,
+    };
+    format!("{:?} at {}", C::VALUE, pixel.intensity)
+}
+
+fn main() {
+    for color in [Color::Red, Color::Green] {
+        println!("{}", dispatch_color!(color, |C| describe::<C>(255)));
+    }
+}
@@ -0,0 +1,87 @@
+//! Compares the expansion of `#[sealed]` on the impls of a large generated
+//! crate, parsing them fully or only up to their body.
+//!
+//! Run with `cargo bench -p sealed-core`.
+
+use std::time::{Duration, Instant};
+
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use sealed_core::{seal_impl, seal_impl_header, ImplHeader, ImplOptions};
+
+const IMPLS: usize = 500;
+const METHODS: usize = 20;
+const RUNS: u32 = 10;
+
+/// Generates the impls of a crate with `IMPLS` implementors of a sealed trait
+/// of `METHODS` methods, each with a non-trivial body.
+fn generate() -> Vec<TokenStream> {
+    (0..IMPLS)
+        .map(|i| {
+            let ty = format_ident!("Type{}", i);
+            let methods = (0..METHODS).map(|m| {
+                let method = format_ident!("method{}", m);
+                quote! {
+                    fn #method(&self, input: &[u32]) -> Option<u32> {
+                        let mut total = 0u32;
+                        for (i, value) in input.iter().enumerate() {
+                            if i % 2 == 0 {
+                                total = total.checked_add(*value)?;
+                            } else {
+                                total = match value.checked_mul(#m as u32) {
+                                    Some(value) => total.saturating_sub(value),
+                                    None => return None,
+                                };
+                            }
+                        }
+                        Some(total)
+                    }
+                }
+            });
+            quote! {
+                impl<T: Clone> crate::backends::Backend<T> for #ty<T>
+                where
+                    T: Send + 'static,
+                {
+                    #(#methods)*
+                }
+            }
+        })
+        .collect()
+}
+
+/// Runs `expand` on every impl `RUNS` times, returning the mean duration of
+/// a run.
+fn measure(impls: &[TokenStream], expand: impl Fn(TokenStream) -> TokenStream) -> Duration {
+    let mut tokens = 0;
+    let start = Instant::now();
+    for _ in 0..RUNS {
+        for item in impls {
+            tokens += expand(item.clone()).into_iter().count();
+        }
+    }
+    let elapsed = start.elapsed() / RUNS;
+    assert!(tokens > 0);
+    elapsed
+}
+
+fn main() {
+    let impls = generate();
+
+    let full = measure(&impls, |tokens| {
+        let item_impl: syn::ItemImpl = syn::parse2(tokens).unwrap();
+        seal_impl(&item_impl, ImplOptions::default()).unwrap()
+    });
+    let header = measure(&impls, |tokens| {
+        let header: ImplHeader = syn::parse2(tokens).unwrap();
+        seal_impl_header(&header, ImplOptions::default()).unwrap()
+    });
+
+    println!("{} impls of {} methods:", IMPLS, METHODS);
+    println!("  full item parsing: {:>8.2?}", full);
+    println!("  header parsing:    {:>8.2?}", header);
+    println!(
+        "  speedup:           {:>8.2}x",
+        full.as_secs_f64() / header.as_secs_f64()
+    );
+}
@@ -0,0 +1,2327 @@
+//! # `sealed-core`
+//!
+//! The expansion logic of the [`#[sealed]`](https://docs.rs/sealed) attribute,
+//! for procedural macros emitting sealed traits or impls of them, so they stay
+//! in sync with the attribute instead of copying its naming rules.
+//!
+//! ```rust,ignore
+//! use sealed_core::{seal_impl, ImplOptions};
+//!
+//! #[proc_macro_derive(Backend)]
+//! pub fn derive_backend(input: TokenStream) -> TokenStream {
+//!     let input = parse_macro_input!(input as syn::DeriveInput);
+//!     let ident = &input.ident;
+//!     let item_impl = parse_quote!(impl ::my_crate::Backend for #ident {});
+//!     // as if written `#[sealed(derived)] impl ::my_crate::Backend for ... {}`
+//!     let options: ImplOptions = parse_quote!(derived);
+//!     seal_impl(&item_impl, options)
+//!         .unwrap_or_else(|e| e.to_compile_error())
+//!         .into()
+//! }
+//! ```
+//!
+//! [`Options`] and [`ImplOptions`] parse the same arguments as the attribute
+//! on traits and impls respectively, and [`seal_trait`] and [`seal_impl`]
+//! generate the same code. [`seal_path_for`] and [`seal_name`] give the path
+//! and name of the sealing module generated for a trait,
+//! [`implementors_path_for`] the path of the macro listing its implementors,
+//! and
+//! [`trait_variant_name`] the name of the variant created by
+//! `#[trait_variant::make]`, whose impls reach the seal under that name.
+//!
+//! Expanding an impl never looks into its items, so [`ImplHeader`] parses it
+//! only up to its body, which [`seal_impl_header`] expands. This is how the
+//! attribute expands impls, as parsing the items of large impls dominates the
+//! expansion time otherwise.
+//!
+//! The generated code names the hidden modules of this crate version, so
+//! `sealed-core` has to be kept at the same version as `sealed`.
+
+use std::{borrow::Cow, fmt};
+
+use proc_macro2::{Span, TokenStream as TokenStream2, TokenTree};
+use quote::{format_ident, quote, ToTokens as _};
+use syn::{
+    ext::IdentExt,
+    parse::{Parse, ParseStream},
+    parse_quote,
+    spanned::Spanned,
+    token,
+};
+
+/// Expands `#[sealed(...)]` on `item_trait`, with the arguments of the
+/// attribute given as `options`.
+///
+/// The trait is given the `Sealed` supertrait of its sealing module, which is
+/// generated next to it, together with the code required by `options`.
+// Care for https://gist.github.com/Kestrer/8c05ebd4e0e9347eb05f265dfb7252e1#procedural-macros-support-renaming-the-crate
+pub fn seal_trait(mut item_trait: syn::ItemTrait, options: Options) -> syn::Result<TokenStream2> {
+    let args = options;
+    if let Some(lint) = trait_variant_lint(&item_trait.attrs) {
+        // the trait is kept unsealed, so that the blanket impl of the variant
+        // does not bury this error under unsatisfied bounds on the seal
+        let error = syn::Error::new_spanned(
+            lint,
+            "`#[trait_variant::make]` has to be placed below `#[sealed]`, so \
+             that the variant is sealed together with the trait",
+        )
+        .to_compile_error();
+        return Ok(quote! { #error #item_trait });
+    }
+    if let Some(by) = &args.by {
+        return parse_sealed_by(item_trait, by, &args);
+    }
+
+    let trait_ident = &item_trait.ident.unraw();
+    let trait_generics = &item_trait.generics;
+    let seal = seal_name(trait_ident);
+    let vis = &args.visibility;
+
+    if args.implementors.is_some() && !trait_generics.params.is_empty() {
+        return Err(syn::Error::new_spanned(
+            trait_generics,
+            "`implementors(...)` is not supported on generic traits",
+        ));
+    }
+    if let Some(serde) = &args.serde {
+        if args.implementors.is_none() {
+            return Err(syn::Error::new(
+                serde.span(),
+                "`serde` requires the set of implementors to be closed with \
+                 `implementors(...)`",
+            ));
+        }
+    } else if let Some(attr) = args
+        .implementors
+        .iter()
+        .flatten()
+        .flat_map(|i| &i.attrs)
+        .find(|attr| attr.path().is_ident("serde"))
+    {
+        return Err(syn::Error::new_spanned(
+            attr,
+            "`#[serde(...)]` on implementors requires the `serde` argument",
+        ));
+    }
+    if let Some(ids) = &args.ids {
+        if args.implementors.is_none() {
+            return Err(syn::Error::new(
+                ids.span(),
+                "`ids` requires the set of implementors to be closed with \
+                 `implementors(...)`",
+            ));
+        }
+    }
+    if let Some(unsealed_if) = &args.unsealed_if {
+        if !args.dyn_traits.is_empty()
+            || args.implementors.is_some()
+            || args.serde.is_some()
+            || args.ids.is_some()
+        {
+            return Err(syn::Error::new_spanned(
+                unsealed_if,
+                "`unsealed_if` cannot be combined with `dyn`, `implementors`, \
+                 `serde` or `ids`, as they rely on the seal",
+            ));
+        }
+    }
+    if let Some(derive) = &args.derive {
+        if args.implementors.is_some() {
+            return Err(syn::Error::new(
+                derive.span(),
+                "`derive` cannot be combined with `implementors`, as derived \
+                 types cannot be listed",
+            ));
+        }
+    }
+    if let Some(extend_macro) = &args.extend_macro {
+        if !trait_generics.params.is_empty() {
+            return Err(syn::Error::new_spanned(
+                trait_generics,
+                "`extend_macro` is not supported on generic traits",
+            ));
+        }
+        if args.implementors.is_some() {
+            return Err(syn::Error::new_spanned(
+                &extend_macro.name,
+                "`extend_macro` cannot be combined with `implementors`, as \
+                 the extending types cannot be listed",
+            ));
+        }
+    }
+    if let Some(escape_hatch) = &args.escape_hatch {
+        if !trait_generics.params.is_empty() {
+            return Err(syn::Error::new_spanned(
+                trait_generics,
+                "`escape_hatch` is not supported on generic traits",
+            ));
+        }
+        if args.implementors.is_some() {
+            return Err(syn::Error::new(
+                escape_hatch.span(),
+                "`escape_hatch` cannot be combined with `implementors`, as the \
+                 set of implementors cannot be closed",
+            ));
+        }
+        if let Some(unsafety) = &item_trait.unsafety {
+            return Err(syn::Error::new_spanned(
+                unsafety,
+                "`escape_hatch` is not supported on `unsafe` traits, as the \
+                 `unsafe` of its impls would be ambiguous",
+            ));
+        }
+    }
+    let variant = trait_variant_name(&item_trait.attrs)?;
+    if let Some(mock) = &args.mock {
+        if variant.is_some() {
+            return Err(syn::Error::new(
+                mock.span(),
+                "`mock` cannot be combined with `trait_variant::make`, as the \
+                 variant cannot be mocked",
+            ));
+        }
+        if args.implementors.is_some() || !args.dyn_traits.is_empty() {
+            return Err(syn::Error::new(
+                mock.span(),
+                "`mock` cannot be combined with `dyn` or `implementors`, as the \
+                 mock would have to implement the listed traits or be listed",
+            ));
+        }
+    }
+    if !args.dyn_traits.is_empty() {
+        if args.erased {
+            return Err(syn::Error::new(
+                args.dyn_traits[0].span(),
+                "`dyn` cannot be combined with `erase`, as the generated \
+                 helpers need to name the sealed trait",
+            ));
+        }
+        if !trait_generics.params.is_empty() {
+            return Err(syn::Error::new_spanned(
+                trait_generics,
+                "`dyn` is not supported on generic traits",
+            ));
+        }
+    }
+
+    let (_, ty_generics, where_clause) = trait_generics.split_for_impl();
+
+    let mut seal_code = SealCode::default();
+    seal_code.dyn_traits(&item_trait.ident, &seal, &args.dyn_traits);
+    if let Some(implementors) = &args.implementors {
+        seal_code.implementors(&item_trait, &seal, implementors)?;
+        if args.serde.is_some() {
+            seal_code.serde(&item_trait.ident, &seal, implementors);
+        }
+        if args.ids.is_some() {
+            seal_code.ids(&item_trait, &seal, implementors);
+        }
+    }
+
+    let mod_code = if args.erased {
+        let lifetimes = trait_generics.lifetimes();
+        let const_params = trait_generics.const_params();
+        let type_params =
+            trait_generics
+                .type_params()
+                .map(|syn::TypeParam { ident, .. }| -> syn::TypeParam {
+                    parse_quote!( #ident : ?Sized )
+                });
+
+        let seal_supertraits = &seal_code.supertraits;
+        let helpers = &seal_code.helpers;
+
+        quote! {
+            pub trait Sealed< #(#lifetimes ,)* #(#type_params ,)* #(#const_params ,)* >
+                : #(#seal_supertraits)+*
+            {}
+            #helpers
+        }
+    } else {
+        let mut trait_supertraits = item_trait.supertraits.clone();
+        trait_supertraits.extend(seal_code.supertraits.iter().cloned());
+        let helpers = &seal_code.helpers;
+
+        // `trait_generics` does not output its where clause when tokenized (due
+        // to supertraits in the middle). So we output them separately.
+        quote! {
+            use super::*;
+            pub trait Sealed #trait_generics : #trait_supertraits #where_clause {}
+            #helpers
+        }
+    };
+    let seal_impls = &seal_code.impls;
+
+    // Friends reach the seal through a public module named after them, as
+    // there is no visibility spanning several crates.
+    let friend_seals = args
+        .friends
+        .iter()
+        .map(|friend| {
+            let name = friend.value().replace('-', "_");
+            syn::parse_str::<syn::Ident>(&name)
+                .map(|krate| exposed_seal_name(trait_ident, &format!("friend_{}", krate)))
+                .map_err(|_| syn::Error::new(friend.span(), "expected a crate name"))
+        })
+        .collect::<syn::Result<Vec<_>>>()?;
+    let derive_seal = args.derive.as_ref().map(|_| {
+        let derive_seal = exposed_seal_name(trait_ident, "derive");
+        quote! {
+            #[doc(hidden)]
+            pub mod #derive_seal {
+                pub use super::#seal::Sealed;
+            }
+        }
+    });
+
+    let extend_macro = args
+        .extend_macro
+        .as_ref()
+        .map(|extend_macro| extend_macro.expand(&item_trait, &seal))
+        .transpose()?;
+
+    // `automock` goes first, so it sees the trait before any other attribute
+    // macro, as `mockall` requires.
+    let mock_seal = args.mock.as_ref().map(|_| {
+        item_trait.attrs.insert(
+            0,
+            parse_quote!(#[cfg_attr(any(test, feature = "mock"), ::mockall::automock)]),
+        );
+        let mock = format_ident!("Mock{}", trait_ident);
+        let (impl_generics, ty_generics, where_clause) = trait_generics.split_for_impl();
+        quote! {
+            #[cfg(any(test, feature = "mock"))]
+            #[automatically_derived]
+            impl #impl_generics #seal::Sealed #ty_generics for #mock #ty_generics #where_clause {}
+        }
+    });
+
+    // `trait_variant::make` copies the supertraits, so the variant already
+    // shares the seal, which only has to be reachable under its name.
+    let variant_seal = variant.map(|variant| {
+        let variant_seal = seal_name(variant.unraw());
+        quote! {
+            #vis mod #variant_seal {
+                pub use super::#seal::*;
+            }
+        }
+    });
+
+    // Every opted-in impl implements the seal itself, so the other ones fail
+    // on the seal as usual rather than on a blanket impl.
+    let escape_seal = args.escape_hatch.as_ref().map(|_| {
+        let escape_seal = exposed_seal_name(trait_ident, "escape");
+        quote! {
+            #[doc(hidden)]
+            pub mod #escape_seal {
+                pub use super::#seal::Sealed;
+            }
+        }
+    });
+
+    // A blanket `Sealed` impl would conflict with the `#[sealed]` impls, so
+    // the trait is rather emitted twice, with and without the seal.
+    let mut unsealed_trait = None;
+    if let Some(cfg) = &args.unsealed_if {
+        let mut unsealed = item_trait.clone();
+        let cfg_str = quote!(#cfg).to_string();
+        let note = format!(
+            "\n\n**Note:** this trait is unsealed, as `{}` is enabled, so it \
+             may be implemented outside of its crate. This is experimental and \
+             may change without notice.",
+            cfg_str,
+        );
+        unsealed.attrs.push(parse_quote!(#[doc = #note]));
+        let note = format!(
+            "\n\n**Note:** this trait is sealed, unless `{}` is enabled.",
+            cfg_str,
+        );
+        item_trait.attrs.push(parse_quote!(#[cfg(not(#cfg))]));
+        item_trait.attrs.push(parse_quote!(#[doc = #note]));
+        unsealed_trait = Some(quote! {
+            #[cfg(#cfg)]
+            #unsealed
+        });
+    }
+
+    item_trait
+        .attrs
+        .extend(sealed_docs(&args, &item_trait.ident));
+    if let Some(doctest) = &args.doctest {
+        let doc = doctest.expand(&item_trait)?;
+        item_trait
+            .attrs
+            .push(parse_quote!(#[cfg_attr(doctest, doc = #doc)]));
+    }
+
+    item_trait
+        .supertraits
+        .push(parse_quote!( #seal::Sealed #ty_generics ));
+    Ok(quote! {
+        #[automatically_derived]
+        #vis mod #seal {
+            #mod_code
+        }
+        #(
+            #[doc(hidden)]
+            pub mod #friend_seals {
+                pub use super::#seal::*;
+            }
+        )*
+        #variant_seal
+        #derive_seal
+        #escape_seal
+        #extend_macro
+        #item_trait
+        #unsealed_trait
+        #seal_impls
+        #mock_seal
+    })
+}
+
+/// Seals the trait with the seal of another trait, given with `by = ...`, so
+/// its implementors are sealed the same without a sealing module of its own.
+fn parse_sealed_by(
+    mut item_trait: syn::ItemTrait,
+    by: &syn::Path,
+    args: &Options,
+) -> syn::Result<TokenStream2> {
+    if args.erased
+        || !matches!(args.visibility, syn::Visibility::Inherited)
+        || !args.dyn_traits.is_empty()
+        || args.implementors.is_some()
+        || args.serde.is_some()
+        || args.ids.is_some()
+        || !args.friends.is_empty()
+        || args.unsealed_if.is_some()
+        || args.escape_hatch.is_some()
+        || args.derive.is_some()
+        || args.extend_macro.is_some()
+        || args.doctest.is_some()
+        || args.mock.is_some()
+    {
+        return Err(syn::Error::new_spanned(
+            by,
+            "`by` cannot be combined with other arguments, as no sealing \
+             module is generated for the trait",
+        ));
+    }
+
+    let seal_path = seal_path_for(by)?;
+    let arguments = &by.segments.last().unwrap().arguments;
+    item_trait
+        .attrs
+        .extend(sealed_docs(args, &item_trait.ident));
+    item_trait
+        .supertraits
+        .push(parse_quote!( #seal_path::Sealed #arguments ));
+    Ok(quote!(#item_trait))
+}
+
+/// Expands `#[sealed(...)]` on `item_impl`, with the arguments of the
+/// attribute given as `options`.
+///
+/// The impl is accompanied by an impl of the `Sealed` trait of the sealing
+/// module at [`seal_path_for`] the implemented trait, or at the hidden path
+/// exposing it to friends, derive macros and escape hatches.
+pub fn seal_impl(item_impl: &syn::ItemImpl, options: ImplOptions) -> syn::Result<TokenStream2> {
+    let impl_trait = item_impl
+        .trait_
+        .as_ref()
+        .ok_or_else(|| syn::Error::new_spanned(item_impl, "missing implementation trait"))?;
+    let mut item_impl = Cow::Borrowed(item_impl);
+    if options.escape_hatch {
+        check_escape_hatch(item_impl.unsafety, item_impl.impl_token)?;
+        item_impl.to_mut().unsafety = None;
+    }
+    let seal_impls = parse_seal_impls(
+        &impl_trait.1,
+        &item_impl.generics,
+        &item_impl.self_ty,
+        options,
+    )?;
+    Ok(quote! {
+        #seal_impls
+        #item_impl
+    })
+}
+
+/// Expands `#[sealed(...)]` on the impl of `header`, as [`seal_impl`] does,
+/// without having parsed its body.
+pub fn seal_impl_header(header: &ImplHeader, options: ImplOptions) -> syn::Result<TokenStream2> {
+    let mut header = Cow::Borrowed(header);
+    if options.escape_hatch {
+        check_escape_hatch(header.unsafety, header.impl_token)?;
+        header.to_mut().unsafety = None;
+    }
+    let seal_impls = parse_seal_impls(
+        &header.trait_path,
+        &header.generics,
+        &header.self_ty,
+        options,
+    )?;
+    Ok(quote! {
+        #seal_impls
+        #header
+    })
+}
+
+/// Checks that an impl going through the escape hatch of its trait is written
+/// `unsafe impl`, which only marks the opt-in and is then removed.
+fn check_escape_hatch(unsafety: Option<token::Unsafe>, impl_token: token::Impl) -> syn::Result<()> {
+    match unsafety {
+        Some(_) => Ok(()),
+        None => Err(syn::Error::new(
+            impl_token.span,
+            "`escape_hatch` impls have to be written `unsafe impl`, accepting \
+             that any release may break them",
+        )),
+    }
+}
+
+/// Generates the impls of the seal accompanying an impl of `trait_path` for
+/// `self_type`.
+fn parse_seal_impls(
+    trait_path: &syn::Path,
+    generics: &syn::Generics,
+    self_type: &syn::Type,
+    args: ImplOptions,
+) -> syn::Result<TokenStream2> {
+    let exposure = if args.friend {
+        let krate = std::env::var("CARGO_CRATE_NAME").map_err(|_| {
+            syn::Error::new(
+                Span::call_site(),
+                "`friend` requires the crate to be built by Cargo",
+            )
+        })?;
+        Some(format!("friend_{}", krate))
+    } else if args.derived {
+        Some("derive".to_owned())
+    } else if args.escape_hatch {
+        Some("escape".to_owned())
+    } else {
+        None
+    };
+    let seal_path = match exposure {
+        Some(exposure) => {
+            let mut seal_path = trait_path.clone();
+            let last = seal_path.segments.pop().unwrap().into_value();
+            seal_path
+                .segments
+                .push(exposed_seal_name(last.ident.unraw(), &exposure).into());
+            seal_path
+        }
+        None => seal_path_for(trait_path)?,
+    };
+    // since `impl for ...` is not allowed, this path will *always* have at least length 1
+    // thus `last` is safe to unwrap
+    let arguments = &trait_path.segments.last().unwrap().arguments;
+
+    // Only keep the introduced params (no bounds), since
+    // the bounds may break in the `#seal` submodule.
+    let (trait_generics, _, where_clauses) = generics.split_for_impl();
+
+    // Two impls with the same ID implement `IdTaken<ID>` twice for `Ids`,
+    // which is a coherence error.
+    let id_impls = args.id.map(|id| {
+        // Inherent impls are only allowed for local types, which is assumed
+        // of the types named by a plain identifier other than the primitive
+        // and prelude ones.
+        let id_const = is_local_type(self_type).then(|| {
+            quote! {
+                #[automatically_derived]
+                impl #trait_generics #self_type #where_clauses {
+                    /// ID given to this type with `#[sealed(id = ...)]`.
+                    pub const ID: u32 = <Self as #seal_path::Id>::ID;
+                }
+            }
+        });
+        quote! {
+            #[automatically_derived]
+            impl #trait_generics #seal_path::Id for #self_type #where_clauses {
+                const ID: u32 = #id;
+            }
+            #[automatically_derived]
+            impl #seal_path::IdTaken<{ #id }> for #seal_path::Ids {}
+            #id_const
+        }
+    });
+
+    Ok(quote! {
+        #[automatically_derived]
+        impl #trait_generics #seal_path::Sealed #arguments for #self_type #where_clauses {}
+        #id_impls
+    })
+}
+
+/// Returns whether `ty` is presumably defined in the current crate, i.e. it is
+/// named by a single identifier that is neither a primitive type nor one of
+/// the prelude.
+fn is_local_type(ty: &syn::Type) -> bool {
+    const FOREIGN: &[&str] = &[
+        "bool", "char", "str", "u8", "u16", "u32", "u64", "u128", "usize", "i8", "i16", "i32",
+        "i64", "i128", "isize", "f32", "f64", "Box", "Option", "Result", "String", "Vec",
+    ];
+    match ty {
+        syn::Type::Path(ty) if ty.qself.is_none() && ty.path.leading_colon.is_none() => {
+            ty.path.segments.len() == 1
+                && !FOREIGN.iter().any(|name| ty.path.segments[0].ident == name)
+        }
+        _ => false,
+    }
+}
+
+/// Generates the `Sealed` section appended to the docs of a sealed trait, as
+/// the sealing supertrait is not visible in them.
+///
+/// The implementors are listed when known, each respecting its `#[cfg]`.
+fn sealed_docs(args: &Options, trait_ident: &syn::Ident) -> Vec<syn::Attribute> {
+    let text = match &args.doc {
+        Some(doc) => doc.value(),
+        None => {
+            let mut exceptions = Vec::new();
+            if !args.friends.is_empty() {
+                let friends = args
+                    .friends
+                    .iter()
+                    .map(|friend| format!("`{}`", friend.value()))
+                    .collect::<Vec<_>>();
+                exceptions.push(format!(
+                    "by the {} crate{}",
+                    friends.join(", "),
+                    if friends.len() > 1 { "s" } else { "" },
+                ));
+            }
+            if args.derive.is_some() {
+                exceptions.push("through the derive macros of its crate".to_owned());
+            }
+            if let Some(extend_macro) = &args.extend_macro {
+                exceptions.push(format!("with the `{}!` macro", extend_macro.name));
+            }
+            if args.escape_hatch.is_some() {
+                exceptions.push("with an `unsafe` `#[sealed(escape_hatch)]` impl".to_owned());
+            }
+            let mut text =
+                "This trait is sealed and cannot be implemented outside of its crate".to_owned();
+            if !exceptions.is_empty() {
+                text.push_str(", except ");
+                text.push_str(&exceptions.join(" or "));
+            }
+            if let Some(by) = &args.by {
+                text.push_str(&format!(
+                    ", as it shares the seal of `{}`",
+                    quote!(#by).to_string().replace(' ', ""),
+                ));
+            }
+            text.push('.');
+            text
+        }
+    };
+
+    let mut attrs: Vec<syn::Attribute> = vec![
+        parse_quote!(#[doc = "\n\n# Sealed\n"]),
+        parse_quote!(#[doc = #text]),
+    ];
+    if let Some(implementors) = &args.implementors {
+        attrs.push(parse_quote!(#[doc = "\nIt is implemented by:\n"]));
+        for implementor in implementors {
+            let line = format!("- [`{}`]", implementor.ident.unraw());
+            let cfgs = implementor
+                .cfgs()
+                .map(|attr| match &attr.meta {
+                    syn::Meta::List(list) => list.tokens.clone(),
+                    meta => quote!(#meta),
+                })
+                .collect::<Vec<_>>();
+            attrs.push(if cfgs.is_empty() {
+                parse_quote!(#[doc = #line])
+            } else {
+                parse_quote!(#[cfg_attr(all(#(#cfgs),*), doc = #line)])
+            });
+        }
+    }
+    if args.mock.is_some() {
+        let text = format!(
+            "\nIts `Mock{}` mock is sealed as well, and generated in tests or \
+             with the `mock` feature of its crate.",
+            trait_ident.unraw(),
+        );
+        attrs.push(parse_quote!(#[doc = #text]));
+    }
+    attrs
+}
+
+/// Returns the path of the sealing module of the trait at `trait_path`, e.g.
+/// `a::b::__seal_t` for `a::b::T<X>`.
+pub fn seal_path_for(trait_path: &syn::Path) -> syn::Result<syn::Path> {
+    let mut seal_path = trait_path.clone();
+    let last = seal_path
+        .segments
+        .pop()
+        .ok_or_else(|| syn::Error::new_spanned(trait_path, "expected trait path"))?
+        .into_value();
+    seal_path
+        .segments
+        .push(seal_name(last.ident.unraw()).into());
+    Ok(seal_path)
+}
+
+/// Returns the path of the macro passing the `implementors(...)` of the trait
+/// at `trait_path` to a callback macro, e.g. `a::b::__seal_t_implementors_v0_6`
+/// for `a::b::T<X>`.
+///
+/// It is exposed next to the trait to the whole crate, so it resolves from
+/// any module naming the trait through a path of its module.
+pub fn implementors_path_for(trait_path: &syn::Path) -> syn::Result<syn::Path> {
+    let mut path = trait_path.clone();
+    let last = path
+        .segments
+        .pop()
+        .ok_or_else(|| syn::Error::new_spanned(trait_path, "expected trait path"))?
+        .into_value();
+    path.segments
+        .push(exposed_seal_name(last.ident.unraw(), "implementors").into());
+    Ok(path)
+}
+
+/// Convert a string into snake case.
+///
+/// Stolen't from <https://github.com/jmg-duarte/sealed-rs/pull/6#pullrequestreview-653837118>
+fn to_snake_case(s: &'_ str) -> String {
+    let mut ret = String::with_capacity(s.len());
+    let mut first = true;
+    s.chars().for_each(|c| {
+        if c.is_uppercase() {
+            if !first {
+                ret.push('_');
+            }
+            ret.extend(c.to_lowercase());
+        } else {
+            ret.push(c);
+        }
+        first = false;
+    });
+    ret
+}
+
+/// Constructs [`struct@syn::Ident`] of a sealing module name, e.g. `__seal_my_trait`
+/// for `MyTrait`.
+pub fn seal_name<D: fmt::Display>(seal: D) -> syn::Ident {
+    format_ident!("__seal_{}", to_snake_case(&seal.to_string()))
+}
+
+/// Constructs [`struct@syn::Ident`] of the hidden module exposing a sealing module
+/// outside of its crate, e.g. `__seal_t_derive_v0_6`.
+///
+/// It is versioned, so impls built against another version of this crate
+/// fail to resolve it instead of silently mismatching.
+fn exposed_seal_name<D: fmt::Display>(seal: D, exposure: &str) -> syn::Ident {
+    format_ident!(
+        "{}_{}_v{}_{}",
+        seal_name(seal),
+        exposure,
+        env!("CARGO_PKG_VERSION_MAJOR"),
+        env!("CARGO_PKG_VERSION_MINOR"),
+    )
+}
+
+/// Constructs a snake case [`struct@syn::Ident`] from a type name, falling back to
+/// raw identifiers for keywords.
+///
+/// This is a helper of `#[builder]` and `#[type_enum]`, outside of the stable
+/// API.
+#[doc(hidden)]
+pub fn snake_ident(ident: &syn::Ident) -> syn::Ident {
+    let name = to_snake_case(&ident.unraw().to_string());
+    match name.as_str() {
+        "crate" | "self" | "super" => format_ident!("{}_", name),
+        _ => syn::parse_str(&name).unwrap_or_else(|_| syn::Ident::new_raw(&name, ident.span())),
+    }
+}
+
+/// Arguments accepted by `#[sealed]` attribute when placed on a trait
+/// definition, parsed from the tokens inside of its parentheses.
+pub struct Options {
+    /// `erase` argument indicating whether trait bounds erasure should be used.
+    ///
+    /// Default is `false`.
+    erased: bool,
+
+    /// `pub` argument defining visibility of the generated sealing module.
+    ///
+    /// Default is [`syn::Visibility::Inherited`].
+    visibility: syn::Visibility,
+
+    /// `dyn(...)` argument listing the traits to implement for `Box<dyn T>`
+    /// through hidden helpers on the generated `Sealed` trait.
+    ///
+    /// Default is empty.
+    dyn_traits: Vec<DynTrait>,
+
+    /// `implementors(...)` argument closing the set of types allowed to
+    /// implement the trait.
+    ///
+    /// Default is [`None`], allowing any `#[sealed]` impl.
+    implementors: Option<Vec<Implementor>>,
+
+    /// `serde` argument indicating whether `Serialize` and `Deserialize`
+    /// should be implemented for `Box<dyn T>`.
+    ///
+    /// Default is [`None`].
+    serde: Option<syn::Ident>,
+
+    /// `ids` argument requiring every implementor to be given a unique
+    /// numeric ID with `#[sealed(id = ...)]`.
+    ///
+    /// Default is [`None`].
+    ids: Option<syn::Ident>,
+
+    /// `by = ...` argument giving another sealed trait, whose seal is reused
+    /// instead of generating a new one.
+    ///
+    /// Default is [`None`].
+    by: Option<syn::Path>,
+
+    /// `friends(...)` argument naming the crates allowed to implement the
+    /// trait with `#[sealed(friend)]`.
+    ///
+    /// Default is empty.
+    friends: Vec<syn::LitStr>,
+
+    /// `unsealed_if = ...` argument giving a `cfg` predicate under which the
+    /// trait is not sealed.
+    ///
+    /// Default is [`None`].
+    unsealed_if: Option<syn::Meta>,
+
+    /// `escape_hatch` argument exposing the seal to the `unsafe` impls marked
+    /// with `#[sealed(escape_hatch)]`.
+    ///
+    /// Default is [`None`].
+    escape_hatch: Option<syn::Ident>,
+
+    /// `derive` argument exposing the seal to the derive macros of the crate,
+    /// which emit `#[sealed(derived)]` impls.
+    ///
+    /// Default is [`None`].
+    derive: Option<syn::Ident>,
+
+    /// `extend_macro = ...` argument generating an exported `macro_rules!`,
+    /// which implements the trait for a newtype by forwarding to its field.
+    ///
+    /// Default is [`None`].
+    extend_macro: Option<ExtendMacro>,
+
+    /// `doctest` argument, optionally followed by `in some::path` giving the
+    /// module of the trait, appending a `compile_fail` doctest proving that
+    /// the trait is sealed.
+    ///
+    /// Default is [`None`].
+    doctest: Option<Doctest>,
+
+    /// `doc = "..."` argument replacing the wording of the `Sealed` section
+    /// appended to the docs of the trait.
+    ///
+    /// Default is [`None`], using a standard wording.
+    doc: Option<syn::LitStr>,
+
+    /// `mock` argument generating a `mockall` mock of the trait, sealed the
+    /// same as its implementors, in tests or with the `mock` feature.
+    ///
+    /// Default is [`None`].
+    mock: Option<syn::Ident>,
+}
+
+impl Options {
+    /// Returns the visibility of the sealing module, given as `pub(...)`.
+    pub fn visibility(&self) -> &syn::Visibility {
+        &self.visibility
+    }
+
+    /// Returns the trait whose seal is shared, given as `by = ...`.
+    pub fn sealed_by(&self) -> Option<&syn::Path> {
+        self.by.as_ref()
+    }
+
+    /// Returns the `cfg` predicate unsealing the trait, given as
+    /// `unsealed_if = ...`.
+    pub fn unsealed_if(&self) -> Option<&syn::Meta> {
+        self.unsealed_if.as_ref()
+    }
+
+    /// Returns the crates allowed to implement the trait, given as
+    /// `friends(...)`.
+    pub fn friends(&self) -> &[syn::LitStr] {
+        &self.friends
+    }
+
+    /// Returns whether the derive macros of the crate may implement the
+    /// trait, given as `derive`.
+    pub fn is_derivable(&self) -> bool {
+        self.derive.is_some()
+    }
+
+    /// Returns whether the trait may be implemented by `unsafe` impls marked
+    /// with `#[sealed(escape_hatch)]`, given as `escape_hatch`.
+    pub fn has_escape_hatch(&self) -> bool {
+        self.escape_hatch.is_some()
+    }
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            erased: false,
+            visibility: syn::Visibility::Inherited,
+            dyn_traits: Vec::new(),
+            implementors: None,
+            serde: None,
+            ids: None,
+            by: None,
+            friends: Vec::new(),
+            unsealed_if: None,
+            escape_hatch: None,
+            derive: None,
+            extend_macro: None,
+            doctest: None,
+            doc: None,
+            mock: None,
+        }
+    }
+}
+
+impl Parse for Options {
+    fn parse(input: ParseStream<'_>) -> syn::Result<Self> {
+        let mut out = Self::default();
+
+        while !input.is_empty() {
+            let ident = syn::Ident::parse_any(&input.fork())?;
+
+            match ident.to_string().as_str() {
+                "erase" => {
+                    syn::Ident::parse_any(input)?;
+                    out.erased = true;
+                }
+
+                "pub" => {
+                    out.visibility = input.parse()?;
+                    if matches!(out.visibility, syn::Visibility::Public(_)) {
+                        return Err(syn::Error::new(
+                            out.visibility.span(),
+                            "`pub` visibility breaks the seal as allows to use \
+                             it outside its crate.\n\
+                             Consider tightening the visibility (e.g. \
+                             `pub(crate)`) if you actually need sealing.",
+                        ));
+                    }
+                }
+
+                "dyn" => {
+                    syn::Ident::parse_any(input)?;
+                    let content;
+                    syn::parenthesized!(content in input);
+                    out.dyn_traits = content
+                        .parse_terminated(DynTrait::parse, token::Comma)?
+                        .into_iter()
+                        .collect();
+                }
+
+                "implementors" => {
+                    syn::Ident::parse_any(input)?;
+                    let content;
+                    syn::parenthesized!(content in input);
+                    out.implementors = Some(
+                        content
+                            .parse_terminated(Implementor::parse, token::Comma)?
+                            .into_iter()
+                            .collect(),
+                    );
+                }
+
+                "serde" => {
+                    let ident = syn::Ident::parse_any(input)?;
+                    if cfg!(not(feature = "serde")) {
+                        return Err(syn::Error::new(
+                            ident.span(),
+                            "`serde` argument requires the `serde` feature of `sealed`",
+                        ));
+                    }
+                    out.serde = Some(ident);
+                }
+
+                "ids" => {
+                    out.ids = Some(syn::Ident::parse_any(input)?);
+                }
+
+                "friends" => {
+                    syn::Ident::parse_any(input)?;
+                    let content;
+                    syn::parenthesized!(content in input);
+                    out.friends = content
+                        .parse_terminated(<syn::LitStr as Parse>::parse, token::Comma)?
+                        .into_iter()
+                        .collect();
+                }
+
+                "extend_macro" => {
+                    syn::Ident::parse_any(input)?;
+                    input.parse::<token::Eq>()?;
+                    out.extend_macro = Some(input.parse()?);
+                }
+
+                "doc" => {
+                    syn::Ident::parse_any(input)?;
+                    input.parse::<token::Eq>()?;
+                    out.doc = Some(input.parse()?);
+                }
+
+                "doctest" => {
+                    out.doctest = Some(input.parse()?);
+                }
+
+                "derive" => {
+                    out.derive = Some(syn::Ident::parse_any(input)?);
+                }
+
+                "escape_hatch" => {
+                    out.escape_hatch = Some(syn::Ident::parse_any(input)?);
+                }
+
+                "mock" => {
+                    out.mock = Some(syn::Ident::parse_any(input)?);
+                }
+
+                "unsealed_if" => {
+                    syn::Ident::parse_any(input)?;
+                    input.parse::<token::Eq>()?;
+                    out.unsealed_if = Some(input.parse()?);
+                }
+
+                "by" => {
+                    syn::Ident::parse_any(input)?;
+                    input.parse::<token::Eq>()?;
+                    out.by = Some(input.parse()?);
+                }
+
+                unknown => {
+                    return Err(syn::Error::new(
+                        ident.span(),
+                        format!("unknown `{}` attribute argument", unknown),
+                    ))
+                }
+            }
+
+            if input
+                .lookahead1()
+                .peek(token::Comma)
+                .then(|| input.parse::<token::Comma>())
+                .transpose()?
+                .is_none()
+                && !input.is_empty()
+            {
+                return Err(syn::Error::new(ident.span(), "expected followed by `,`"));
+            }
+        }
+
+        Ok(out)
+    }
+}
+
+/// `extend_macro = name` argument, optionally followed by `in some::path`
+/// giving the module of the trait, which is the crate root by default.
+struct ExtendMacro {
+    name: syn::Ident,
+    path: Option<syn::Path>,
+}
+
+impl Parse for ExtendMacro {
+    fn parse(input: ParseStream<'_>) -> syn::Result<Self> {
+        let name = input.parse()?;
+        let path = if input.peek(token::In) {
+            input.parse::<token::In>()?;
+            Some(input.call(syn::Path::parse_mod_style)?)
+        } else {
+            None
+        };
+        Ok(Self { name, path })
+    }
+}
+
+impl ExtendMacro {
+    /// Generates the exported `macro_rules!`, together with the hidden
+    /// module exposing the seal to its expansions.
+    ///
+    /// The macro implements the trait for `$ty` by forwarding every item to
+    /// the `$inner` implementor in its `$field`. The hidden module is public,
+    /// so this only soft seals the trait, like `friends(...)`.
+    fn expand(&self, item_trait: &syn::ItemTrait, seal: &syn::Ident) -> syn::Result<TokenStream2> {
+        let name = &self.name;
+        let trait_ident = &item_trait.ident;
+        let extend_seal = exposed_seal_name(trait_ident.unraw(), "extend");
+
+        // `crate` is only meaningful in the defining crate, so it is replaced
+        // with `$crate` in the expansions.
+        let module = match &self.path {
+            Some(path) => {
+                let mut segments = path.segments.iter().peekable();
+                if segments.peek().map_or(false, |s| s.ident == "crate") {
+                    segments.next();
+                }
+                quote!($crate #(:: #segments)*)
+            }
+            None => quote!($crate),
+        };
+        let trait_path = quote!(#module::#trait_ident);
+        let async_trait = async_trait_attr(&item_trait.attrs);
+
+        let items = item_trait
+            .items
+            .iter()
+            .map(|item| Self::forward(item, &trait_path))
+            .collect::<syn::Result<Vec<_>>>()?;
+
+        let doc = format!(
+            "Implements [`{0}`] for a newtype by forwarding to the implementor \
+             in one of its fields, e.g. `{1}!(MyNewtype => inner: Inner)` or \
+             `{1}!(MyNewtype => 0: Inner)`.",
+            trait_ident.unraw(),
+            name,
+        );
+
+        Ok(quote! {
+            #[doc(hidden)]
+            pub mod #extend_seal {
+                pub use super::#seal::Sealed;
+            }
+
+            #[doc = #doc]
+            #[macro_export]
+            macro_rules! #name {
+                ($ty:ty => $field:tt : $inner:ty $(,)?) => {
+                    impl #module::#extend_seal::Sealed for $ty {}
+
+                    #async_trait
+                    impl #trait_path for $ty {
+                        #(#items)*
+                    }
+                };
+            }
+        })
+    }
+
+    /// Forwards the trait `item` of `$ty` to the `$inner` implementor.
+    fn forward(item: &syn::TraitItem, trait_path: &TokenStream2) -> syn::Result<TokenStream2> {
+        match item {
+            syn::TraitItem::Fn(item_fn) => {
+                let mut sig = item_fn.sig.clone();
+                let fn_ident = &sig.ident;
+
+                let mut args = Vec::with_capacity(sig.inputs.len());
+                for (i, input) in sig.inputs.iter_mut().enumerate() {
+                    match input {
+                        syn::FnArg::Receiver(receiver) => {
+                            if receiver.colon_token.is_some() {
+                                return Err(syn::Error::new_spanned(
+                                    receiver,
+                                    "`extend_macro` only supports `self`, `&self` \
+                                     and `&mut self` receivers",
+                                ));
+                            }
+                            args.push(match (&receiver.reference, &receiver.mutability) {
+                                (Some(_), Some(_)) => quote!(&mut self.$field),
+                                (Some(_), None) => quote!(&self.$field),
+                                (None, _) => quote!(self.$field),
+                            });
+                        }
+                        syn::FnArg::Typed(pat_type) => {
+                            if mentions_bare_self(pat_type.ty.to_token_stream()) {
+                                return Err(syn::Error::new_spanned(
+                                    &pat_type.ty,
+                                    "`extend_macro` cannot forward arguments \
+                                     mentioning `Self` or `impl Trait`",
+                                ));
+                            }
+                            let arg = format_ident!("__arg{}", i);
+                            *pat_type.pat = parse_quote!(#arg);
+                            args.push(quote!(#arg));
+                        }
+                    }
+                }
+                let output = &sig.output;
+                if mentions_bare_self(quote!(#output)) {
+                    return Err(syn::Error::new_spanned(
+                        output,
+                        "`extend_macro` cannot forward a return type \
+                         mentioning `Self`",
+                    ));
+                }
+
+                let generic_args = sig
+                    .generics
+                    .params
+                    .iter()
+                    .filter_map(|param| match param {
+                        syn::GenericParam::Type(param) => Some(&param.ident),
+                        syn::GenericParam::Const(param) => Some(&param.ident),
+                        syn::GenericParam::Lifetime(_) => None,
+                    })
+                    .collect::<Vec<_>>();
+                let turbofish = (!generic_args.is_empty()).then(|| quote!(::<#(#generic_args),*>));
+                let await_ = sig.asyncness.map(|_| quote!(.await));
+
+                Ok(quote! {
+                    #sig {
+                        <$inner as #trait_path>::#fn_ident #turbofish(#(#args),*) #await_
+                    }
+                })
+            }
+            syn::TraitItem::Type(item_type) => {
+                if !item_type.generics.params.is_empty() {
+                    return Err(syn::Error::new_spanned(
+                        &item_type.generics,
+                        "`extend_macro` cannot forward generic associated types",
+                    ));
+                }
+                let ident = &item_type.ident;
+                Ok(quote!(type #ident = <$inner as #trait_path>::#ident;))
+            }
+            syn::TraitItem::Const(item_const) => {
+                let ident = &item_const.ident;
+                let ty = &item_const.ty;
+                Ok(quote!(const #ident: #ty = <$inner as #trait_path>::#ident;))
+            }
+            item => Err(syn::Error::new_spanned(
+                item,
+                "`extend_macro` cannot forward this item",
+            )),
+        }
+    }
+}
+
+/// `doctest` argument, optionally followed by `in some::path` giving the
+/// module of the trait, which is the crate root by default.
+struct Doctest {
+    ident: syn::Ident,
+    path: Option<syn::Path>,
+}
+
+impl Parse for Doctest {
+    fn parse(input: ParseStream<'_>) -> syn::Result<Self> {
+        let ident = syn::Ident::parse_any(input)?;
+        let path = if input.peek(token::In) {
+            input.parse::<token::In>()?;
+            Some(input.call(syn::Path::parse_mod_style)?)
+        } else {
+            None
+        };
+        Ok(Self { ident, path })
+    }
+}
+
+impl Doctest {
+    /// Generates the docs containing the doctests, which are only compiled
+    /// by `rustdoc`.
+    ///
+    /// The `compile_fail` one implements the trait for a fresh type, giving
+    /// every required item, so the seal is the only reason for it to fail.
+    /// Hence traits whose bounds the fresh type may not satisfy are rejected.
+    /// It is accompanied by one that compiles, declaring the same items in a
+    /// local trait, so neither a wrong path nor an unresolved type makes it
+    /// pass unnoticed.
+    fn expand(&self, item_trait: &syn::ItemTrait) -> syn::Result<String> {
+        if !item_trait.generics.params.is_empty() {
+            return Err(syn::Error::new_spanned(
+                &item_trait.generics,
+                "`doctest` is not supported on generic traits",
+            ));
+        }
+        // The fresh type failing any other bound would fail the doctest with
+        // the same error code as the seal, so it would pass even if the seal
+        // did not hold.
+        let self_bounds = where_bounds(&item_trait.generics)
+            .filter(|(ty, _)| matches!(ty, syn::Type::Path(ty) if ty.path.is_ident("Self")))
+            .map(|(_, bound)| bound);
+        if let Some(bound) = item_trait
+            .supertraits
+            .iter()
+            .chain(self_bounds)
+            .find(|bound| may_be_unsatisfied(bound))
+        {
+            return Err(syn::Error::new_spanned(
+                bound,
+                "`doctest` is not supported on traits with supertraits or \
+                 `Self` bounds, as the fresh type of the doctest does not \
+                 satisfy them",
+            ));
+        }
+        if let Some(item_type) = item_trait.items.iter().find_map(|item| match item {
+            syn::TraitItem::Type(item_type)
+                if item_type.default.is_none()
+                    && item_type
+                        .bounds
+                        .iter()
+                        .chain(where_bounds(&item_type.generics).map(|(_, bound)| bound))
+                        .any(may_be_unsatisfied) =>
+            {
+                Some(item_type)
+            }
+            _ => None,
+        }) {
+            return Err(syn::Error::new_spanned(
+                item_type,
+                "`doctest` is not supported on traits with bounded associated \
+                 types, as the `()` type of the doctest does not satisfy them",
+            ));
+        }
+        let krate = std::env::var("CARGO_CRATE_NAME").map_err(|_| {
+            syn::Error::new(
+                self.ident.span(),
+                "`doctest` requires the crate to be built by Cargo",
+            )
+        })?;
+
+        let mut module = krate.clone();
+        for segment in self.path.iter().flat_map(|path| &path.segments) {
+            if segment.ident != "crate" {
+                module.push_str("::");
+                module.push_str(&segment.ident.to_string());
+            }
+        }
+        let krate = format_ident!("{}", krate);
+
+        // The doctests are other crates, where the types of the signatures
+        // are reached through the module of the trait.
+        let mut items = TokenStream2::new();
+        let mut declarations = TokenStream2::new();
+        for item in &item_trait.items {
+            match item {
+                syn::TraitItem::Fn(item_fn) => {
+                    let sig = &item_fn.sig;
+                    declarations.extend(quote!(#sig;));
+                    if item_fn.default.is_none() {
+                        items.extend(quote!(#sig { ::core::unimplemented!() }));
+                    }
+                }
+                syn::TraitItem::Type(item_type) => {
+                    let ident = &item_type.ident;
+                    let generics = &item_type.generics;
+                    declarations.extend(quote!(type #ident #generics;));
+                    if item_type.default.is_none() {
+                        items.extend(quote!(type #ident #generics = ();));
+                    }
+                }
+                syn::TraitItem::Const(item_const) => {
+                    let ident = &item_const.ident;
+                    let ty = &item_const.ty;
+                    declarations.extend(quote!(const #ident: #ty;));
+                    if item_const.default.is_none() {
+                        items.extend(quote!(const #ident: #ty = ::core::unimplemented!();));
+                    }
+                }
+                _ => {}
+            }
+        }
+        let items = replace_crate(items, &krate);
+        let declarations = replace_crate(declarations, &krate);
+        let ident = &item_trait.ident;
+        let async_trait = async_trait_attr(&item_trait.attrs);
+
+        // Error codes of `compile_fail` doctests are only checked on nightly,
+        // so the first doctest fails instead on the other errors of the
+        // second one.
+        Ok(format!(
+            "\n\n\
+             ```\n\
+             #![allow(dead_code, unused_imports)]\n\
+             use {0}::*;\n\
+             use {0}::{1} as _;\n\
+             {4}\n\
+             trait SealedDoctest {{ {3} }}\n\
+             ```\n\n\
+             ```compile_fail,E0277\n\
+             #![allow(dead_code, unused_imports)]\n\
+             use {0}::*;\n\
+             struct SealedDoctest;\n\
+             {4}\n\
+             impl {0}::{1} for SealedDoctest {{ {2} }}\n\
+             ```\n",
+            module,
+            ident,
+            items,
+            declarations,
+            quote!(#async_trait),
+        ))
+    }
+}
+
+/// Replaces the `crate` of the paths in `tokens` by `krate`, naming the crate
+/// from another one.
+fn replace_crate(tokens: TokenStream2, krate: &syn::Ident) -> TokenStream2 {
+    tokens
+        .into_iter()
+        .map(|token| match token {
+            TokenTree::Ident(ident) if ident == "crate" => {
+                TokenTree::Ident(syn::Ident::new(&krate.to_string(), ident.span()))
+            }
+            TokenTree::Group(group) => {
+                let mut replaced = proc_macro2::Group::new(
+                    group.delimiter(),
+                    replace_crate(group.stream(), krate),
+                );
+                replaced.set_span(group.span());
+                TokenTree::Group(replaced)
+            }
+            token => token,
+        })
+        .collect()
+}
+
+/// Arguments accepted by `#[sealed]` attribute when placed on a trait
+/// implementation, parsed from the tokens inside of its parentheses.
+#[derive(Default)]
+pub struct ImplOptions {
+    /// `id = ...` argument giving the implementor a numeric ID, for traits
+    /// sealed with `ids`.
+    ///
+    /// Default is [`None`].
+    id: Option<syn::Expr>,
+
+    /// `friend` argument implementing a trait of another crate, which names
+    /// this one in its `friends(...)`.
+    ///
+    /// Default is `false`.
+    friend: bool,
+
+    /// `derived` argument implementing a trait sealed with `derive`, as
+    /// emitted by the derive macros of its crate.
+    ///
+    /// Default is `false`.
+    derived: bool,
+
+    /// `escape_hatch` argument implementing a trait sealed with
+    /// `escape_hatch` downstream, with an `unsafe impl` accepting breakage.
+    ///
+    /// Default is `false`.
+    escape_hatch: bool,
+}
+
+impl ImplOptions {
+    /// Returns whether the impl is of a trait of another crate, naming this
+    /// one in its `friends(...)`.
+    pub fn is_friend(&self) -> bool {
+        self.friend
+    }
+
+    /// Returns whether the impl is emitted by a derive macro of the crate of
+    /// the trait.
+    pub fn is_derived(&self) -> bool {
+        self.derived
+    }
+
+    /// Returns whether the impl goes through the escape hatch of the trait.
+    pub fn is_escape_hatch(&self) -> bool {
+        self.escape_hatch
+    }
+}
+
+impl Parse for ImplOptions {
+    fn parse(input: ParseStream<'_>) -> syn::Result<Self> {
+        let mut out = Self::default();
+
+        while !input.is_empty() {
+            let ident = syn::Ident::parse_any(input)?;
+
+            match ident.to_string().as_str() {
+                "id" => {
+                    input.parse::<token::Eq>()?;
+                    out.id = Some(input.parse()?);
+                }
+
+                "friend" => out.friend = true,
+
+                "derived" => out.derived = true,
+
+                "escape_hatch" => out.escape_hatch = true,
+
+                // Accepted for symmetry with the trait, as the impl is the
+                // same whether the bounds are erased or not.
+                "erase" => {}
+
+                unknown => {
+                    return Err(syn::Error::new(
+                        ident.span(),
+                        format!("unknown `{}` attribute argument", unknown),
+                    ))
+                }
+            }
+
+            if input
+                .lookahead1()
+                .peek(token::Comma)
+                .then(|| input.parse::<token::Comma>())
+                .transpose()?
+                .is_none()
+                && !input.is_empty()
+            {
+                return Err(syn::Error::new(ident.span(), "expected followed by `,`"));
+            }
+        }
+
+        Ok(out)
+    }
+}
+
+/// Trait impl parsed up to its body, which is kept as raw tokens, as
+/// expanding `#[sealed]` on an impl never looks into its items.
+///
+/// Parsing the items of large impls with `syn` dominates the expansion time
+/// otherwise.
+#[derive(Clone)]
+pub struct ImplHeader {
+    attrs: Vec<syn::Attribute>,
+    unsafety: Option<token::Unsafe>,
+    impl_token: token::Impl,
+    generics: syn::Generics,
+    trait_path: syn::Path,
+    for_token: token::For,
+    self_ty: syn::Type,
+    body: proc_macro2::Group,
+}
+
+impl Parse for ImplHeader {
+    fn parse(input: ParseStream<'_>) -> syn::Result<Self> {
+        let attrs = input.call(syn::Attribute::parse_outer)?;
+        let unsafety = input.parse()?;
+        let impl_token = input.parse()?;
+        let mut generics: syn::Generics = input.parse()?;
+        let trait_path = input.parse()?;
+        let for_token = input
+            .parse()
+            .map_err(|e| syn::Error::new(e.span(), "missing implementation trait"))?;
+        let self_ty = input.parse()?;
+        generics.where_clause = input.parse()?;
+        let body: proc_macro2::Group = input.parse()?;
+        if body.delimiter() != proc_macro2::Delimiter::Brace {
+            return Err(syn::Error::new(body.span(), "expected `{`"));
+        }
+        Ok(Self {
+            attrs,
+            unsafety,
+            impl_token,
+            generics,
+            trait_path,
+            for_token,
+            self_ty,
+            body,
+        })
+    }
+}
+
+impl quote::ToTokens for ImplHeader {
+    fn to_tokens(&self, tokens: &mut TokenStream2) {
+        let Self {
+            attrs,
+            unsafety,
+            impl_token,
+            generics,
+            trait_path,
+            for_token,
+            self_ty,
+            body,
+        } = self;
+        let where_clause = &generics.where_clause;
+        tokens.extend(quote! {
+            #(#attrs)*
+            #unsafety #impl_token #generics #trait_path #for_token #self_ty #where_clause #body
+        });
+    }
+}
+
+/// Trait that may be implemented for `Box<dyn T>` with the `dyn(...)`
+/// argument.
+enum DynTrait {
+    Clone(syn::Ident),
+    PartialEq(syn::Ident),
+    Hash(syn::Ident),
+    Debug(syn::Ident),
+}
+
+impl DynTrait {
+    fn span(&self) -> Span {
+        match self {
+            Self::Clone(ident)
+            | Self::PartialEq(ident)
+            | Self::Hash(ident)
+            | Self::Debug(ident) => ident.span(),
+        }
+    }
+}
+
+impl Parse for DynTrait {
+    fn parse(input: ParseStream<'_>) -> syn::Result<Self> {
+        let ident: syn::Ident = input.parse()?;
+        match ident.to_string().as_str() {
+            "Clone" => Ok(Self::Clone(ident)),
+            "PartialEq" => Ok(Self::PartialEq(ident)),
+            "Hash" => Ok(Self::Hash(ident)),
+            "Debug" => Ok(Self::Debug(ident)),
+            unknown => Err(syn::Error::new(
+                ident.span(),
+                format!(
+                    "unsupported `{}` in `dyn(...)`, expected one of \
+                     `Clone`, `PartialEq`, `Hash` or `Debug`",
+                    unknown,
+                ),
+            )),
+        }
+    }
+}
+
+/// Entry of the `implementors(...)` argument.
+struct Implementor {
+    /// Attributes of the entry, either `#[cfg(...)]` or `#[serde(...)]`.
+    attrs: Vec<syn::Attribute>,
+
+    /// Implementing type, which must be nameable from the module of the trait.
+    ident: syn::Ident,
+
+    /// Optional constructor of the implementing type, given as `= expr`.
+    constructor: Option<syn::Expr>,
+}
+
+impl Implementor {
+    /// Returns the `#[cfg(...)]` attributes of this entry, which must be
+    /// repeated on every item generated for it.
+    fn cfgs(&self) -> impl Iterator<Item = &syn::Attribute> {
+        self.attrs.iter().filter(|attr| attr.path().is_ident("cfg"))
+    }
+
+    /// Returns the name of this implementor, as shown by the registry.
+    fn name(&self) -> syn::LitStr {
+        syn::LitStr::new(&self.ident.unraw().to_string(), self.ident.span())
+    }
+}
+
+impl Parse for Implementor {
+    fn parse(input: ParseStream<'_>) -> syn::Result<Self> {
+        let attrs = input.call(syn::Attribute::parse_outer)?;
+        for attr in &attrs {
+            if !attr.path().is_ident("cfg") && !attr.path().is_ident("serde") {
+                return Err(syn::Error::new_spanned(
+                    attr,
+                    "only `#[cfg(...)]` and `#[serde(...)]` attributes are \
+                     supported on implementors",
+                ));
+            }
+        }
+        let ident = input.parse()?;
+        let constructor = input
+            .peek(token::Eq)
+            .then(|| -> syn::Result<_> {
+                input.parse::<token::Eq>()?;
+                input.parse()
+            })
+            .transpose()?;
+        Ok(Self {
+            attrs,
+            ident,
+            constructor,
+        })
+    }
+}
+
+/// Additional code generated for a sealed trait by its arguments.
+#[derive(Default)]
+struct SealCode {
+    /// Helper bounds to append to the `Sealed` supertraits.
+    supertraits: Vec<syn::TypeParamBound>,
+
+    /// Helper traits and their blanket impls, placed in the sealing module.
+    helpers: TokenStream2,
+
+    /// Items placed next to the trait, such as impls for `dyn T`.
+    impls: TokenStream2,
+
+    /// Whether the `DynAny` helper was already added.
+    has_dyn_any: bool,
+}
+
+impl SealCode {
+    /// Generates the helpers of the `dyn(...)` argument.
+    ///
+    /// Every helper is a supertrait of the generated `Sealed` trait, implemented
+    /// with a blanket impl, so `#[sealed]` impls get them for free as long as
+    /// the implementor provides the corresponding trait (e.g. `Clone`).
+    fn dyn_traits(&mut self, trait_ident: &syn::Ident, seal: &syn::Ident, dyn_traits: &[DynTrait]) {
+        let needs_any = dyn_traits
+            .iter()
+            .any(|dyn_trait| matches!(dyn_trait, DynTrait::PartialEq(_)));
+
+        for dyn_trait in dyn_traits {
+            match dyn_trait {
+                DynTrait::Clone(_) => {
+                    self.supertraits.push(parse_quote!(DynClone));
+                    self.helpers.extend(quote! {
+                        pub trait DynClone {
+                            fn __clone_box(&self) -> ::std::boxed::Box<dyn super::#trait_ident>;
+                        }
+                        impl<X> DynClone for X
+                        where
+                            X: super::#trait_ident + ::core::clone::Clone + 'static,
+                        {
+                            fn __clone_box(&self) -> ::std::boxed::Box<dyn super::#trait_ident> {
+                                ::std::boxed::Box::new(::core::clone::Clone::clone(self))
+                            }
+                        }
+                    });
+                    self.impls.extend(quote! {
+                        impl ::core::clone::Clone for ::std::boxed::Box<dyn #trait_ident> {
+                            fn clone(&self) -> Self {
+                                #seal::DynClone::__clone_box(&**self)
+                            }
+                        }
+                    });
+                }
+                DynTrait::PartialEq(_) => {
+                    self.supertraits.push(parse_quote!(DynPartialEq));
+                    self.helpers.extend(quote! {
+                        pub trait DynPartialEq {
+                            fn __dyn_eq(&self, other: &dyn super::#trait_ident) -> bool;
+                        }
+                        impl<X> DynPartialEq for X
+                        where
+                            X: ::core::cmp::PartialEq + ::core::any::Any,
+                        {
+                            fn __dyn_eq(&self, other: &dyn super::#trait_ident) -> bool {
+                                DynAny::__as_any(other)
+                                    .downcast_ref::<X>()
+                                    .map_or(false, |other| self == other)
+                            }
+                        }
+                    });
+                    self.impls.extend(quote! {
+                        impl ::core::cmp::PartialEq for dyn #trait_ident {
+                            fn eq(&self, other: &Self) -> bool {
+                                #seal::DynPartialEq::__dyn_eq(self, other)
+                            }
+                        }
+                    });
+                }
+                DynTrait::Hash(_) => {
+                    self.supertraits.push(parse_quote!(DynHash));
+                    self.helpers.extend(quote! {
+                        pub trait DynHash {
+                            fn __dyn_hash(&self, state: &mut dyn ::core::hash::Hasher);
+                        }
+                        impl<X> DynHash for X
+                        where
+                            X: ::core::hash::Hash + ::core::any::Any,
+                        {
+                            fn __dyn_hash(&self, mut state: &mut dyn ::core::hash::Hasher) {
+                                // Values of different implementors should not
+                                // collide just because their contents do.
+                                ::core::hash::Hash::hash(&::core::any::TypeId::of::<X>(), &mut state);
+                                ::core::hash::Hash::hash(self, &mut state);
+                            }
+                        }
+                    });
+                    self.impls.extend(quote! {
+                        impl ::core::hash::Hash for dyn #trait_ident {
+                            fn hash<H: ::core::hash::Hasher>(&self, state: &mut H) {
+                                #seal::DynHash::__dyn_hash(self, state)
+                            }
+                        }
+                    });
+                }
+                DynTrait::Debug(_) => {
+                    // `dyn T` implements its supertraits on its own.
+                    self.supertraits.push(parse_quote!(::core::fmt::Debug));
+                }
+            }
+        }
+
+        if needs_any {
+            self.dyn_any();
+        }
+    }
+
+    /// Adds the `DynAny` helper, allowing to downcast `dyn T` to the
+    /// implementors.
+    fn dyn_any(&mut self) {
+        if self.has_dyn_any {
+            return;
+        }
+        self.has_dyn_any = true;
+        self.supertraits.push(parse_quote!(DynAny));
+        self.helpers.extend(quote! {
+            pub trait DynAny {
+                fn __as_any(&self) -> &dyn ::core::any::Any;
+            }
+            impl<X: ::core::any::Any> DynAny for X {
+                fn __as_any(&self) -> &dyn ::core::any::Any {
+                    self
+                }
+            }
+        });
+    }
+
+    /// Generates `Serialize` for `dyn T` and `Deserialize` for `Box<dyn T>`
+    /// of the `serde` argument.
+    ///
+    /// Both go through private enums with a newtype variant per implementor,
+    /// which are externally tagged by `serde`. Serialization downcasts to
+    /// every implementor in turn, while deserialization simply boxes the
+    /// deserialized variant.
+    fn serde(&mut self, trait_ident: &syn::Ident, seal: &syn::Ident, implementors: &[Implementor]) {
+        self.dyn_any();
+
+        let cfgs = implementors
+            .iter()
+            .map(|i| i.cfgs().collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        let serde_attrs = implementors
+            .iter()
+            .map(|i| {
+                let serde_attrs = i
+                    .attrs
+                    .iter()
+                    .filter(|attr| attr.path().is_ident("serde"))
+                    .collect::<Vec<_>>();
+                // Tag with the name of the implementor, unless renamed.
+                let renamed = serde_attrs.iter().any(|attr| {
+                    let mut renamed = false;
+                    let _ = attr.parse_nested_meta(|meta| {
+                        renamed |= meta.path.is_ident("rename");
+                        Ok(())
+                    });
+                    renamed
+                });
+                let name = i.name();
+                let rename = (!renamed).then(|| quote!(#[serde(rename = #name)]));
+                quote!(#rename #(#serde_attrs)*)
+            })
+            .collect::<Vec<_>>();
+        let idents = implementors.iter().map(|i| &i.ident).collect::<Vec<_>>();
+        let trait_name = syn::LitStr::new(&trait_ident.unraw().to_string(), trait_ident.span());
+
+        self.impls.extend(quote! {
+            const _: () = {
+                #[derive(::serde::Serialize)]
+                #[serde(rename = #trait_name)]
+                enum Borrowed<'a> {
+                    #(
+                        #(#cfgs)*
+                        #serde_attrs
+                        #idents(&'a #idents),
+                    )*
+                    #[serde(skip)]
+                    #[allow(dead_code)]
+                    __Lifetime(::core::marker::PhantomData<&'a ()>),
+                }
+
+                #[derive(::serde::Deserialize)]
+                #[serde(rename = #trait_name)]
+                enum Owned {
+                    #(
+                        #(#cfgs)*
+                        #serde_attrs
+                        #idents(#idents),
+                    )*
+                }
+
+                impl ::serde::Serialize for dyn #trait_ident {
+                    fn serialize<S>(&self, serializer: S) -> ::core::result::Result<S::Ok, S::Error>
+                    where
+                        S: ::serde::Serializer,
+                    {
+                        let any = #seal::DynAny::__as_any(self);
+                        #(
+                            #(#cfgs)*
+                            if let ::core::option::Option::Some(value) = any.downcast_ref::<#idents>() {
+                                return ::serde::Serialize::serialize(&Borrowed::#idents(value), serializer);
+                            }
+                        )*
+                        ::core::unreachable!("the implementors of a sealed trait are known")
+                    }
+                }
+
+                impl<'de> ::serde::Deserialize<'de> for ::std::boxed::Box<dyn #trait_ident> {
+                    fn deserialize<D>(deserializer: D) -> ::core::result::Result<Self, D::Error>
+                    where
+                        D: ::serde::Deserializer<'de>,
+                    {
+                        ::core::result::Result::Ok(match <Owned as ::serde::Deserialize>::deserialize(deserializer)? {
+                            #(
+                                #(#cfgs)*
+                                Owned::#idents(value) => ::std::boxed::Box::new(value),
+                            )*
+                        })
+                    }
+                }
+            };
+        });
+    }
+
+    /// Generates the checks and the registry of the `implementors(...)`
+    /// argument.
+    ///
+    /// The generated `Sealed` trait requires a `ListedImplementor` supertrait,
+    /// which is only implemented for the listed types, thus any other
+    /// `#[sealed]` impl fails. Conversely, every listed type is asserted to
+    /// implement the trait.
+    fn implementors(
+        &mut self,
+        item_trait: &syn::ItemTrait,
+        seal: &syn::Ident,
+        implementors: &[Implementor],
+    ) -> syn::Result<()> {
+        let trait_ident = &item_trait.ident;
+        let vis = &item_trait.vis;
+        let cfgs = implementors
+            .iter()
+            .map(|i| i.cfgs().collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        let idents = implementors.iter().map(|i| &i.ident).collect::<Vec<_>>();
+        let names = implementors
+            .iter()
+            .map(Implementor::name)
+            .collect::<Vec<_>>();
+        let constructors = implementors.iter().map(|i| match &i.constructor {
+            Some(constructor) => quote! {
+                ::core::option::Option::Some(|| -> ::std::boxed::Box<dyn #trait_ident> {
+                    ::std::boxed::Box::new((#constructor)())
+                })
+            },
+            None => quote!(::core::option::Option::None),
+        });
+
+        let test_names = implementors
+            .iter()
+            .map(|i| snake_ident(&i.ident))
+            .collect::<Vec<_>>();
+
+        self.supertraits.push(parse_quote!(ListedImplementor));
+        self.helpers.extend(quote! {
+            pub trait ListedImplementor {}
+
+            // Passes the listed implementors to `$callback`, which is how
+            // `#[sealed::test_each]` learns about them.
+            #[allow(unused_macros)]
+            macro_rules! implementors {
+                ($callback:ident ! { $($args:tt)* }) => {
+                    $callback! { $($args)* #( #(#cfgs)* #test_names => #idents ),* }
+                };
+            }
+            #[allow(unused_imports)]
+            pub(crate) use implementors;
+        });
+        // The macro is exposed next to the trait, so that modules which are
+        // not descendants of its own may reach it, as the sealing module is
+        // private to them.
+        let exposed = exposed_seal_name(trait_ident.unraw(), "implementors");
+        self.impls.extend(quote! {
+            #[doc(hidden)]
+            #[allow(unused_imports)]
+            pub(crate) use #seal::implementors as #exposed;
+
+            #(
+                #(#cfgs)*
+                impl #seal::ListedImplementor for #idents {}
+            )*
+
+            const _: () = {
+                fn assert_implementor<X: ?::core::marker::Sized + #trait_ident>() {}
+                fn assert_implementors() {
+                    #(
+                        #(#cfgs)*
+                        assert_implementor::<#idents>();
+                    )*
+                }
+            };
+        });
+
+        // The registry lives on a holder type, which is always generated so
+        // that `assert_implementors!` may find it by name, and on `dyn T`
+        // when there is one, as there is no other way of adding items to a
+        // trait without requiring `Self`.
+        let holder = format_ident!("{}Implementors", trait_ident.unraw());
+        let doc = format!(
+            "Registry of the types implementing the sealed [`{}`] trait.",
+            trait_ident.unraw(),
+        );
+        self.impls.extend(quote! {
+            #[doc = #doc]
+            #vis struct #holder;
+
+            impl #holder {
+                /// Names of the types implementing the sealed trait, in
+                /// the order they are listed. Implementors disabled by
+                /// `#[cfg]` are omitted.
+                #vis const IMPLEMENTORS: &'static [&'static str] = &[
+                    #( #(#cfgs)* #names, )*
+                ];
+
+                /// Returns the number of types implementing the sealed
+                /// trait.
+                #vis const fn count() -> usize {
+                    Self::IMPLEMENTORS.len()
+                }
+            }
+        });
+        if !is_object_safe(item_trait) {
+            if let Some(constructor) = implementors.iter().find_map(|i| i.constructor.as_ref()) {
+                return Err(syn::Error::new_spanned(
+                    constructor,
+                    "constructors of implementors require the trait to be \
+                     object safe, as they return `Box<dyn Trait>`",
+                ));
+            }
+            return Ok(());
+        }
+        self.impls.extend(quote! {
+            impl dyn #trait_ident {
+                /// Names of the types implementing this sealed trait, in the
+                /// order they are listed. Implementors disabled by `#[cfg]`
+                /// are omitted.
+                #vis const IMPLEMENTORS: &'static [&'static str] = #holder::IMPLEMENTORS;
+
+                /// Returns the number of types implementing this sealed trait.
+                #vis const fn count() -> usize {
+                    Self::IMPLEMENTORS.len()
+                }
+
+                /// Calls `f` with the name of every type implementing this
+                /// sealed trait, together with its type-erased constructor,
+                /// if one was given.
+                #vis fn for_each_implementor<F>(mut f: F)
+                where
+                    F: ::core::ops::FnMut(
+                        &'static str,
+                        ::core::option::Option<fn() -> ::std::boxed::Box<dyn #trait_ident>>,
+                    ),
+                {
+                    #(
+                        #(#cfgs)*
+                        f(#names, #constructors);
+                    )*
+                }
+            }
+        });
+        Ok(())
+    }
+
+    /// Generates the checks and the lookups of the `ids` argument.
+    ///
+    /// Every listed implementor is asserted to implement the `Id` helper,
+    /// which `#[sealed(id = ...)]` does alongside claiming its ID.
+    fn ids(
+        &mut self,
+        item_trait: &syn::ItemTrait,
+        seal: &syn::Ident,
+        implementors: &[Implementor],
+    ) {
+        let trait_ident = &item_trait.ident;
+        let vis = &item_trait.vis;
+        let cfgs = implementors
+            .iter()
+            .map(|i| i.cfgs().collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        let idents = implementors.iter().map(|i| &i.ident).collect::<Vec<_>>();
+        let names = implementors
+            .iter()
+            .map(Implementor::name)
+            .collect::<Vec<_>>();
+
+        self.helpers.extend(quote! {
+            pub trait Id {
+                const ID: u32;
+            }
+            pub struct Ids;
+            pub trait IdTaken<const ID: u32> {}
+        });
+        self.impls.extend(quote! {
+            const _: () = {
+                fn assert_id<X: ?::core::marker::Sized + #seal::Id>() {}
+                fn assert_ids() {
+                    #(
+                        #(#cfgs)*
+                        assert_id::<#idents>();
+                    )*
+                }
+            };
+        });
+
+        // The lookups live next to the registry of `implementors(...)`, as
+        // the `Id` helper cannot be named outside of the crate.
+        let registry = if is_object_safe(item_trait) {
+            quote!(dyn #trait_ident)
+        } else {
+            let holder = format_ident!("{}Implementors", trait_ident.unraw());
+            quote!(#holder)
+        };
+        self.impls.extend(quote! {
+            impl #registry {
+                /// Returns the ID given to `X` with `#[sealed(id = ...)]`.
+                #vis const fn id_of<X: ?::core::marker::Sized + #seal::Id>() -> u32 {
+                    <X as #seal::Id>::ID
+                }
+
+                /// Returns the name of the type implementing this sealed
+                /// trait with the given ID, if any.
+                #vis fn name_of_id(id: u32) -> ::core::option::Option<&'static str> {
+                    #(
+                        #(#cfgs)*
+                        if id == <#idents as #seal::Id>::ID {
+                            return ::core::option::Option::Some(#names);
+                        }
+                    )*
+                    ::core::option::Option::None
+                }
+            }
+        });
+
+        if !is_object_safe(item_trait) {
+            return;
+        }
+        let builds = implementors.iter().map(|i| match &i.constructor {
+            Some(constructor) => quote! {
+                ::core::option::Option::Some(::std::boxed::Box::new((#constructor)()))
+            },
+            None => quote!(::core::option::Option::None),
+        });
+        self.dyn_any();
+        self.impls.extend(quote! {
+            impl dyn #trait_ident {
+                /// Builds a value of the type implementing this sealed trait
+                /// with the given ID, with the constructor given to it in
+                /// `implementors(...)`. Returns [`None`] if there is no such
+                /// type, or if it was given no constructor.
+                #vis fn from_id(id: u32) -> ::core::option::Option<::std::boxed::Box<dyn #trait_ident>> {
+                    #(
+                        #(#cfgs)*
+                        if id == <#idents as #seal::Id>::ID {
+                            return #builds;
+                        }
+                    )*
+                    ::core::option::Option::None
+                }
+
+                /// Returns the ID given to the type of this value with
+                /// `#[sealed(id = ...)]`.
+                #vis fn id(&self) -> u32 {
+                    let any = #seal::DynAny::__as_any(self);
+                    #(
+                        #(#cfgs)*
+                        if any.is::<#idents>() {
+                            return <#idents as #seal::Id>::ID;
+                        }
+                    )*
+                    ::core::unreachable!("the implementors of a sealed trait are known")
+                }
+            }
+        });
+    }
+}
+
+/// Conservatively checks whether `item_trait` can be made into an object,
+/// i.e. `dyn Trait` is a valid type without specifying associated types.
+fn is_object_safe(item_trait: &syn::ItemTrait) -> bool {
+    let requires_sized = |generics: &syn::Generics| {
+        generics.where_clause.iter().any(|where_clause| {
+            where_clause.predicates.iter().any(|predicate| {
+                matches!(predicate, syn::WherePredicate::Type(syn::PredicateType {
+                    bounded_ty: syn::Type::Path(ty),
+                    bounds,
+                    ..
+                }) if ty.path.is_ident("Self") && bounds.iter().any(is_sized_bound))
+            })
+        })
+    };
+
+    // Any other supertrait, e.g. `Clone` or `PartialEq`, may require `Self`
+    // to be sized or use it in a signature.
+    let self_bounds = where_bounds(&item_trait.generics)
+        .filter(|(ty, _)| matches!(ty, syn::Type::Path(ty) if ty.path.is_ident("Self")))
+        .map(|(_, bound)| bound);
+    item_trait.generics.params.is_empty()
+        && item_trait
+            .supertraits
+            .iter()
+            .chain(self_bounds)
+            .all(is_object_safe_bound)
+        && item_trait.items.iter().all(|item| match item {
+            syn::TraitItem::Fn(item_fn) => {
+                let sig = &item_fn.sig;
+                let output = &sig.output;
+                requires_sized(&sig.generics)
+                    || (sig.receiver().is_some()
+                        && sig.asyncness.is_none()
+                        && sig.generics.type_params().next().is_none()
+                        && !sig
+                            .inputs
+                            .iter()
+                            .skip(1)
+                            .any(|input| mentions_self(quote!(#input)))
+                        && !mentions_self(quote!(#output)))
+            }
+            _ => false,
+        })
+}
+
+/// Checks whether `bound` is a lifetime or one of the standard traits known
+/// to be object safe, such as the auto traits.
+fn is_object_safe_bound(bound: &syn::TypeParamBound) -> bool {
+    match bound {
+        syn::TypeParamBound::Trait(bound) => bound.path.segments.last().map_or(false, |s| {
+            s.arguments.is_empty()
+                && [
+                    "Send",
+                    "Sync",
+                    "Unpin",
+                    "UnwindSafe",
+                    "RefUnwindSafe",
+                    "Any",
+                    "Debug",
+                    "Display",
+                ]
+                .iter()
+                .any(|name| s.ident == name)
+        }),
+        syn::TypeParamBound::Lifetime(_) => true,
+        _ => false,
+    }
+}
+
+/// Checks whether `tokens` contain `Self` or an `impl Trait` type.
+fn mentions_self(tokens: TokenStream2) -> bool {
+    tokens.into_iter().any(|token| match token {
+        proc_macro2::TokenTree::Ident(ident) => ident == "Self" || ident == "impl",
+        proc_macro2::TokenTree::Group(group) => mentions_self(group.stream()),
+        _ => false,
+    })
+}
+
+/// Checks whether `tokens` contain `Self` or an `impl Trait` type, not
+/// counting paths to associated items like `Self::Item`.
+fn mentions_bare_self(tokens: TokenStream2) -> bool {
+    let mut tokens = tokens.into_iter().peekable();
+    while let Some(token) = tokens.next() {
+        match token {
+            proc_macro2::TokenTree::Ident(ident) if ident == "impl" => return true,
+            proc_macro2::TokenTree::Ident(ident)
+                if ident == "Self"
+                    && !matches!(tokens.peek(), Some(proc_macro2::TokenTree::Punct(p)) if p.as_char() == ':') =>
+            {
+                return true;
+            }
+            proc_macro2::TokenTree::Group(group) if mentions_bare_self(group.stream()) => {
+                return true;
+            }
+            _ => {}
+        }
+    }
+    false
+}
+
+/// Returns the bounds of the where clause of `generics`, together with the
+/// type they bound.
+fn where_bounds(
+    generics: &syn::Generics,
+) -> impl Iterator<Item = (&syn::Type, &syn::TypeParamBound)> {
+    generics
+        .where_clause
+        .iter()
+        .flat_map(|where_clause| &where_clause.predicates)
+        .filter_map(|predicate| match predicate {
+            syn::WherePredicate::Type(predicate) => Some(predicate),
+            _ => None,
+        })
+        .flat_map(|predicate| {
+            predicate
+                .bounds
+                .iter()
+                .map(move |bound| (&predicate.bounded_ty, bound))
+        })
+}
+
+/// Checks whether `bound` may be unsatisfied by a fresh unit struct or `()`,
+/// i.e. it is neither a lifetime nor `Sized`.
+fn may_be_unsatisfied(bound: &syn::TypeParamBound) -> bool {
+    matches!(bound, syn::TypeParamBound::Trait(_)) && !is_sized_bound(bound)
+}
+
+/// Checks whether `bound` is `Sized`.
+fn is_sized_bound(bound: &syn::TypeParamBound) -> bool {
+    matches!(bound, syn::TypeParamBound::Trait(bound)
+        if bound.path.segments.last().map_or(false, |s| s.ident == "Sized"))
+}
+
+/// Returns the `#[async_trait]` attribute among `attrs`, with an absolute
+/// path, so it may be applied to the impls generated for the trait.
+fn async_trait_attr(attrs: &[syn::Attribute]) -> Option<syn::Attribute> {
+    let attr = attrs.iter().find(|attr| {
+        let segments = attr.path().segments.iter().map(|s| s.ident.to_string());
+        matches!(
+            segments.collect::<Vec<_>>().as_slice(),
+            [name] | [_, name] if name == "async_trait",
+        )
+    })?;
+    Some(match &attr.meta {
+        syn::Meta::List(list) => {
+            let tokens = &list.tokens;
+            parse_quote!(#[::async_trait::async_trait(#tokens)])
+        }
+        _ => parse_quote!(#[::async_trait::async_trait]),
+    })
+}
+
+/// Returns the `#[allow(async_fn_in_trait)]` attribute among `attrs` which
+/// `#[trait_variant::make]` adds when expanded above `#[sealed]`.
+///
+/// It is told from a written one as all of its tokens share the span of the
+/// `#[trait_variant::make]` attribute, which does not rely on the source text
+/// of the span, unavailable before Rust 1.66 and for the output of macros.
+fn trait_variant_lint(attrs: &[syn::Attribute]) -> Option<&syn::Attribute> {
+    fn spans(tokens: TokenStream2, out: &mut Vec<String>) {
+        for token in tokens {
+            out.push(format!("{:?}", token.span()));
+            if let TokenTree::Group(group) = token {
+                spans(group.stream(), out);
+            }
+        }
+    }
+
+    attrs.iter().find(|attr| match &attr.meta {
+        syn::Meta::List(list) if list.path.is_ident("allow") => {
+            let mut out = Vec::new();
+            spans(attr.to_token_stream(), &mut out);
+            list.tokens.to_string() == "async_fn_in_trait" && out.windows(2).all(|w| w[0] == w[1])
+        }
+        _ => false,
+    })
+}
+
+/// Returns the name of the trait created by `#[trait_variant::make]` among
+/// `attrs`, if it creates one rather than rewriting the trait.
+///
+/// The variant of a `#[sealed]` trait shares its seal, which is reachable
+/// under the [`seal_name`] of the variant as well.
+pub fn trait_variant_name(attrs: &[syn::Attribute]) -> syn::Result<Option<syn::Ident>> {
+    let attr = attrs.iter().find(|attr| {
+        let segments = attr.path().segments.iter().map(|s| s.ident.to_string());
+        matches!(
+            segments.collect::<Vec<_>>().as_slice(),
+            [krate, make] if krate == "trait_variant" && make == "make",
+        )
+    });
+    match attr {
+        Some(attr) => attr.parse_args_with(|input: ParseStream<'_>| {
+            let name = (input.peek(syn::Ident) && input.peek2(token::Colon))
+                .then(|| input.parse())
+                .transpose()?;
+            input.parse::<TokenStream2>()?;
+            Ok(name)
+        }),
+        None => Ok(None),
+    }
+}
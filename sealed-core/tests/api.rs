@@ -0,0 +1,269 @@
+use quote::quote;
+use sealed_core::{
+    seal_impl, seal_impl_header, seal_name, seal_path_for, seal_trait, ImplHeader, ImplOptions,
+    Options,
+};
+use syn::parse_quote;
+
+#[test]
+fn seal_name_follows_trait_name() {
+    assert_eq!(seal_name("MyTrait"), "__seal_my_trait");
+    assert_eq!(seal_name("T"), "__seal_t");
+}
+
+#[test]
+fn seal_path_replaces_trait() {
+    let path = seal_path_for(&parse_quote!(a::b::MyTrait<X>)).unwrap();
+    assert_eq!(
+        quote!(#path).to_string(),
+        quote!(a::b::__seal_my_trait).to_string()
+    );
+}
+
+#[test]
+fn seal_trait_adds_seal() {
+    let options: Options = parse_quote!(implementors(A, B));
+    let tokens = seal_trait(
+        parse_quote!(
+            pub trait MyTrait {}
+        ),
+        options,
+    )
+    .unwrap();
+    let file: syn::File = syn::parse2(tokens).unwrap();
+
+    assert!(file.items.iter().any(|item| matches!(
+        item,
+        syn::Item::Mod(item_mod) if item_mod.ident == "__seal_my_trait"
+    )));
+    let item_trait = file
+        .items
+        .iter()
+        .find_map(|item| match item {
+            syn::Item::Trait(item_trait) if item_trait.ident == "MyTrait" => Some(item_trait),
+            _ => None,
+        })
+        .unwrap();
+    let supertraits = &item_trait.supertraits;
+    assert_eq!(
+        quote!(#supertraits).to_string(),
+        quote!(__seal_my_trait::Sealed).to_string(),
+    );
+}
+
+/// Returns the `#[doc]` and `#[cfg_attr(..., doc = ...)]` attributes of the
+/// trait expanded by `seal_trait`, the latter as tokens.
+fn trait_docs(item_trait: syn::ItemTrait, options: Options) -> Vec<String> {
+    let tokens = seal_trait(item_trait, options).unwrap();
+    let file: syn::File = syn::parse2(tokens).unwrap();
+    let item_trait = file
+        .items
+        .into_iter()
+        .find_map(|item| match item {
+            syn::Item::Trait(item_trait) => Some(item_trait),
+            _ => None,
+        })
+        .unwrap();
+    item_trait
+        .attrs
+        .iter()
+        .filter_map(|attr| match &attr.meta {
+            syn::Meta::NameValue(meta) if meta.path.is_ident("doc") => match &meta.value {
+                syn::Expr::Lit(syn::ExprLit {
+                    lit: syn::Lit::Str(doc),
+                    ..
+                }) => Some(doc.value()),
+                _ => None,
+            },
+            syn::Meta::List(meta) if meta.path.is_ident("cfg_attr") => {
+                Some(quote!(#attr).to_string())
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn seal_trait_documents_seal() {
+    let docs = trait_docs(
+        parse_quote!(
+            /// Docs of the trait.
+            pub trait MyTrait {}
+        ),
+        Options::default(),
+    );
+    assert_eq!(
+        docs,
+        [
+            " Docs of the trait.",
+            "\n\n# Sealed\n",
+            "This trait is sealed and cannot be implemented outside of its crate.",
+        ],
+    );
+}
+
+#[test]
+fn seal_trait_documents_implementors() {
+    let docs = trait_docs(
+        parse_quote!(
+            pub trait MyTrait {}
+        ),
+        parse_quote!(implementors(
+            A,
+            #[cfg(feature = "b")]
+            B
+        )),
+    );
+    assert_eq!(
+        docs,
+        [
+            "\n\n# Sealed\n".to_owned(),
+            "This trait is sealed and cannot be implemented outside of its crate.".to_owned(),
+            "\nIt is implemented by:\n".to_owned(),
+            "- [`A`]".to_owned(),
+            quote!(#[cfg_attr(all(feature = "b"), doc = "- [`B`]")]).to_string(),
+        ],
+    );
+}
+
+#[test]
+fn seal_trait_documents_exceptions() {
+    let docs = trait_docs(
+        parse_quote!(
+            pub trait MyTrait {}
+        ),
+        parse_quote!(friends("a", "b"), derive),
+    );
+    assert_eq!(
+        docs[1],
+        "This trait is sealed and cannot be implemented outside of its crate, \
+         except by the `a`, `b` crates or through the derive macros of its crate.",
+    );
+}
+
+#[test]
+fn seal_trait_documents_custom_wording() {
+    let docs = trait_docs(
+        parse_quote!(
+            pub trait MyTrait {}
+        ),
+        parse_quote!(doc = "Only `A` may implement this trait.", implementors(A)),
+    );
+    assert_eq!(
+        docs,
+        [
+            "\n\n# Sealed\n",
+            "Only `A` may implement this trait.",
+            "\nIt is implemented by:\n",
+            "- [`A`]",
+        ],
+    );
+}
+
+#[test]
+fn seal_trait_rejects_invalid_options() {
+    let options: Options = parse_quote!(implementors(A), extend_macro = impl_t);
+    let error = seal_trait(
+        parse_quote!(
+            pub trait T {}
+        ),
+        options,
+    )
+    .unwrap_err();
+    assert!(error
+        .to_string()
+        .contains("`extend_macro` cannot be combined"));
+}
+
+#[test]
+fn seal_trait_rejects_trait_variant_above() {
+    // Tokens built outside of the compiler have no source text, as is the
+    // case of every span before Rust 1.66, while the attribute emitted by
+    // `trait_variant` shares a single span.
+    let tokens = seal_trait(
+        parse_quote!(
+            #[allow(async_fn_in_trait)]
+            pub trait LocalBackend {
+                async fn connect(&self) -> u8;
+            }
+        ),
+        Options::default(),
+    )
+    .unwrap();
+    assert!(tokens
+        .to_string()
+        .contains("`#[trait_variant::make]` has to be placed below `#[sealed]`"));
+}
+
+#[test]
+fn options_parse_like_the_attribute() {
+    assert!(syn::parse_str::<Options>("erase, pub(crate), dyn(Clone)").is_ok());
+    assert!(syn::parse_str::<Options>("pub").is_err());
+    assert!(syn::parse_str::<Options>("unknown").is_err());
+    assert!(syn::parse_str::<ImplOptions>("id = 7").is_ok());
+}
+
+#[test]
+fn seal_impl_implements_seal() {
+    let item_impl = parse_quote!(impl a::MyTrait for A {});
+    let tokens = seal_impl(&item_impl, ImplOptions::default()).unwrap();
+    let file: syn::File = syn::parse2(tokens).unwrap();
+
+    let traits = file
+        .items
+        .iter()
+        .filter_map(|item| match item {
+            syn::Item::Impl(item_impl) => item_impl.trait_.as_ref().map(|t| &t.1),
+            _ => None,
+        })
+        .map(|path| quote!(#path).to_string())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        traits,
+        [
+            quote!(a::__seal_my_trait::Sealed).to_string(),
+            quote!(a::MyTrait).to_string(),
+        ],
+    );
+}
+
+#[test]
+fn derived_impl_uses_exposed_seal() {
+    let item_impl = parse_quote!(impl a::MyTrait for A {});
+    let tokens = seal_impl(&item_impl, parse_quote!(derived)).unwrap();
+    assert!(tokens.to_string().contains(&format!(
+        "__seal_my_trait_derive_v{}",
+        env!("CARGO_PKG_VERSION_MAJOR")
+    )));
+}
+
+#[test]
+fn impl_header_expands_as_impl() {
+    let tokens = quote! {
+        #[cfg(test)]
+        unsafe impl<T: Clone> a::MyTrait<T> for Vec<T>
+        where
+            T: Send,
+        {
+            fn get(&self) -> T { self[0].clone() }
+        }
+    };
+    let header: ImplHeader = syn::parse2(tokens.clone()).unwrap();
+    let item_impl: syn::ItemImpl = syn::parse2(tokens).unwrap();
+    assert_eq!(
+        seal_impl_header(&header, parse_quote!(id = 7))
+            .unwrap()
+            .to_string(),
+        seal_impl(&item_impl, parse_quote!(id = 7))
+            .unwrap()
+            .to_string(),
+    );
+}
+
+#[test]
+fn impl_header_requires_trait() {
+    match syn::parse_str::<ImplHeader>("impl A { fn f() {} }") {
+        Err(error) => assert_eq!(error.to_string(), "missing implementation trait"),
+        Ok(_) => panic!("parsed an inherent impl"),
+    }
+}
@@ -59,6 +59,226 @@
 //! impl __seal_t::Sealed for A {}
 //! ```
 //!
+//! As the sealing supertrait is invisible in the docs, a `Sealed` section is
+//! appended to the docs of the trait, stating that it cannot be implemented
+//! outside of its crate, and listing its `implementors(...)` when given. Its
+//! wording may be replaced with `#[sealed(doc = "...")]`.
+//!
+//! `#[sealed]` composes with [`async_trait`](https://docs.rs/async-trait) in
+//! any order, on traits and impls alike, and the impls it generates for
+//! `doctest` and `extend_macro` are given the `#[async_trait]` attribute of
+//! the trait. With [`trait_variant`](https://docs.rs/trait-variant),
+//! `#[sealed]` has to come first: the variant created by
+//! `#[trait_variant::make(SendTrait: Send)]` then shares the seal of the
+//! trait, and may be implemented with `#[sealed]` as well. The other order
+//! cannot be supported, as `#[sealed]` would then never see the variant: with
+//! a `Send` variant, it is rejected with an error pointing at
+//! `#[trait_variant::make]`.
+//!
+//! ```rust,ignore
+//! #[sealed]
+//! #[trait_variant::make(Backend: Send)]
+//! pub trait LocalBackend {
+//!     async fn connect(&self) -> Connection;
+//! }
+//!
+//! #[sealed]
+//! impl Backend for Postgres {
+//!     async fn connect(&self) -> Connection { /* ... */ }
+//! }
+//! ```
+//!
+//! The shape of the expansion is stable, so semver tooling working on rustdoc
+//! JSON may classify changes of the trait as those of a sealed trait: its
+//! supertraits always include `__seal_{trait_name}::Sealed`, whose module is
+//! never part of the public API, be it private or `pub(crate)`. Traits sealed
+//! with `by = OtherTrait` are bound by `OtherTrait` instead, and the hidden
+//! paths of `friends(...)`, `derive`, `extend_macro` and `escape_hatch` are
+//! `#[doc(hidden)]`.
+//!
+//! ## Testing every implementor
+//!
+//! The `#[sealed::test_each(Trait)]` attribute expands a generic function into
+//! one `#[test]` per implementor listed with `implementors(...)`, named after
+//! the implementor and placed in a module named after the function. As the
+//! list is checked against the `#[sealed]` impls, a new implementor cannot be
+//! forgotten.
+//!
+//! The list is read through a `#[doc(hidden)]` macro exposed to the crate
+//! next to the trait, so the attribute works from any module of the crate,
+//! as long as the trait is named by a path through its module, e.g.
+//! `crate::backends::Backend`, or its module is glob imported, e.g. with
+//! `use super::*`. Importing the trait alone does not import the macro.
+//!
+//! ```rust
+//! # use sealed::sealed;
+//! #
+//! #[sealed(implementors(Idle, Flying))]
+//! pub trait DroneState {}
+//!
+//! pub struct Idle;
+//! #[sealed]
+//! impl DroneState for Idle {}
+//!
+//! pub struct Flying;
+//! #[sealed]
+//! impl DroneState for Flying {}
+//!
+//! #[cfg(test)]
+//! mod tests {
+//!     use super::*;
+//!
+//!     // generates the `check::idle` and `check::flying` tests
+//!     #[sealed::test_each(DroneState)]
+//!     fn check<S: DroneState>() {
+//!         assert_eq!(std::mem::size_of::<S>(), 0);
+//!     }
+//! }
+//! # fn main() {}
+//! ```
+//!
+//! ## Asserting seals
+//!
+//! Integration tests of a crate may check its sealed traits from the outside
+//! with two macros:
+//!
+//! - `sealed::assert_sealed!(my_crate::T)` writes a case implementing `T` for
+//!   a fresh type to `tests/sealed/`, and checks with [`trybuild`] that it
+//!   fails to compile. As any `trybuild` case, it fails on the first run,
+//!   writing the actual compiler output to `wip/`, which has to be reviewed
+//!   and moved next to the case. Requires a dev-dependency on `trybuild`.
+//! - `sealed::assert_implementors!(my_crate::T: A, B, C)` checks that every
+//!   listed type implements `T`, and that these are exactly its implementors,
+//!   as listed with `implementors(...)`, read from `{Trait}Implementors`.
+//!
+//! ```rust,ignore
+//! #[test]
+//! fn backends() {
+//!     sealed::assert_sealed!(my_crate::Backend);
+//!     sealed::assert_implementors!(my_crate::Backend: my_crate::Postgres, my_crate::Sqlite);
+//! }
+//! ```
+//!
+//! [`trybuild`]: https://docs.rs/trybuild
+//!
+//! ## Procedural macros
+//!
+//! The expansion logic is available to other procedural macros through the
+//! [`sealed-core`](https://docs.rs/sealed-core) crate, so derive macros
+//! emitting sealed traits or impls of them stay in sync with the attribute:
+//! `seal_trait` and `seal_impl` expand a trait or impl with the same
+//! arguments, parsed as `Options` and `ImplOptions`, and `seal_path_for` and
+//! `seal_name` give the path and name of the sealing module of a trait.
+//!
+//! ## Auditing
+//!
+//! The [`cargo-sealed`](https://docs.rs/cargo-sealed) subcommand reports the
+//! sealed surface of a crate from its sources, without compiling it:
+//! `cargo sealed audit` lists the `#[sealed]` traits with their sealing
+//! modules and the `#[sealed]` impls with the seals they implement, and fails
+//! on impls implementing a seal that no trait of the crate produces. The
+//! report is available as text, JSON or SARIF. `cargo sealed semver OLD NEW`
+//! compares the sealed surface of two checkouts of a crate, reporting traits
+//! that became sealed or unsealed, and the methods and implementors added to
+//! or removed from sealed traits.
+//!
+//! ## Open traits
+//!
+//! `#[sealed::open]` marks a public trait as deliberately implementable
+//! downstream. It expands to the trait unchanged, but lets
+//! `cargo sealed check` enforce that every public trait is either
+//! `#[sealed]` or `#[sealed::open]`, so leaving a trait open is never an
+//! accident. The check may also run in a `build.rs` through
+//! `cargo_sealed::check::build()`.
+//!
+//! ```rust
+//! /// Implemented by plugins of other crates.
+//! #[sealed::open]
+//! pub trait Plugin {}
+//! ```
+//!
+//! ## Typestate
+//!
+//! The `sealed::typestate!` macro generates a state machine in the typestate
+//! pattern from a struct and the transitions between its states: a marker type
+//! per state, a `{Struct}State` trait sealed over exactly these markers, the
+//! struct made generic over its state (through an added `state: PhantomData`
+//! field), a `new` constructor taking every field in the initial state, which
+//! is the source of the first transition, and a consuming method per
+//! transition. A transition may take arguments, each setting the field of the
+//! same name, while the other fields are moved to the new state. Calling a
+//! transition from the wrong state fails to compile.
+//!
+//! ```rust
+//! sealed::typestate! {
+//!     pub struct Drone {
+//!         altitude: f32,
+//!     }
+//!
+//!     Idle -> Hovering: take_off(altitude: f32),
+//!     /// Lands the drone.
+//!     Hovering -> Idle: land,
+//! }
+//!
+//! let drone: Drone<Idle> = Drone::new(0.0);
+//! let drone: Drone<Hovering> = drone.take_off(10.0);
+//! assert_eq!(drone.altitude, 10.0);
+//! let drone: Drone<Idle> = drone.land();
+//! // drone.land(); // compile error: no method `land` on `Drone<Idle>`
+//! ```
+//!
+//! ## Type-level enums
+//!
+//! The `#[sealed::type_enum]` attribute mirrors an enum of unit variants as
+//! marker types, so its values may be used as generic parameters. Every
+//! variant gets a marker struct of the same name, implementing a
+//! `{Enum}Kind` trait sealed over exactly these markers, whose `VALUE`
+//! constant is the variant. A `dispatch_{enum}!` macro turns a runtime value
+//! back into a monomorphized call, by binding its marker to the given name.
+//!
+//! ```rust
+//! #[sealed::type_enum]
+//! #[derive(Debug, PartialEq)]
+//! pub enum Color {
+//!     Red,
+//!     Green,
+//! }
+//!
+//! fn value<C: ColorKind>() -> Color {
+//!     C::VALUE
+//! }
+//!
+//! let color = Color::Green;
+//! assert_eq!(dispatch_color!(color, |C| value::<C>()), Color::Green);
+//! assert_eq!(Red::VALUE, Color::Red);
+//! ```
+//!
+//! The enum and its markers are resolved where `dispatch_{enum}!` is used,
+//! unless their module is given with `#[sealed::type_enum(in crate::path)]`.
+//!
+//! ## Typed builders
+//!
+//! The `#[sealed::builder]` attribute generates a `{Struct}Builder` for a
+//! struct with named fields, tracking whether each required field is set with
+//! the `Set` and `Unset` markers of a `{struct}_builder` module. So `build()`
+//! only exists once every required field is set, and setting a field twice
+//! fails to compile. The markers implement a sealed `FieldState` trait, so no
+//! other state can be forged. Fields marked with `#[builder(default)]` or
+//! `#[builder(default = ...)]` are optional.
+//!
+//! ```rust
+//! #[sealed::builder]
+//! pub struct Request {
+//!     pub url: String,
+//!     #[builder(default = 30)]
+//!     pub timeout: u32,
+//! }
+//!
+//! let request = Request::builder().url("https://crates.io".to_owned()).build();
+//! assert_eq!(request.timeout, 30);
+//! // Request::builder().build(); // compile error: `url` is not set
+//! ```
+//!
 //! ## Arguments
 //!
 //! The expanded code may be customized with the following attribute arguments.
@@ -78,6 +298,352 @@
 //! }
 //! ```
 //!
+//! ### `dyn(Clone, PartialEq, Hash, Debug)`
+//!
+//! Implements the listed traits for `Box<dyn T>` (or `dyn T`), so sealed trait
+//! objects may be cloned, compared, hashed and printed. The required helpers
+//! live in the private sealing module, as supertraits of the generated
+//! `Sealed` trait, thus they don't pollute the public API of the trait.
+//! They're implemented automatically for every `#[sealed]` implementor which
+//! implements the respective trait.
+//!
+//! ```rust
+//! # use sealed::sealed;
+//! #
+//! #[sealed(dyn(Clone, PartialEq, Debug))]
+//! pub trait Shape {}
+//!
+//! #[derive(Clone, PartialEq, Debug)]
+//! pub struct Square(u32);
+//! #[sealed]
+//! impl Shape for Square {}
+//!
+//! fn main() {
+//!     let a: Box<dyn Shape> = Box::new(Square(1));
+//!     let b = a.clone();
+//!     assert!(*a == *b);
+//! }
+//! ```
+//!
+//! Values of different implementors are never equal. The trait must be object
+//! safe and defined at module level, thus generic traits, traits inside
+//! functions and `erase` are not supported.
+//!
+//! ### `implementors(...)`
+//!
+//! Closes the set of types allowed to implement the trait. A `#[sealed]` impl
+//! for a type missing from the list fails to compile, as does a listed type
+//! not implementing the trait. Entries are names of types reachable from the
+//! module of the trait, optionally followed by a constructor
+//! (`= fn() -> Type`) and preceded by `#[cfg(...)]` attributes.
+//!
+//! The list is also queryable at runtime, respecting the `#[cfg(...)]` of
+//! every entry, through `IMPLEMENTORS` and `count()` on a generated
+//! `{Trait}Implementors` type, and on `dyn T`. When the trait is not provably
+//! object safe, e.g. as it has associated types, generic methods, or
+//! supertraits other than standard ones such as `Send` or `Debug`, there is
+//! no `dyn T` to host them, and constructors are rejected, as there is no
+//! `Box<dyn T>` to return.
+//!
+//! ```rust
+//! # use sealed::sealed;
+//! #
+//! #[sealed(implementors(Postgres = Postgres::default, #[cfg(feature = "sqlite")] Sqlite))]
+//! pub trait Backend {}
+//!
+//! #[derive(Default)]
+//! pub struct Postgres;
+//! #[sealed]
+//! impl Backend for Postgres {}
+//!
+//! #[cfg(feature = "sqlite")]
+//! pub struct Sqlite;
+//! #[cfg(feature = "sqlite")]
+//! #[sealed]
+//! impl Backend for Sqlite {}
+//!
+//! fn main() {
+//!     assert_eq!(<dyn Backend>::IMPLEMENTORS, ["Postgres"]);
+//!     assert_eq!(<dyn Backend>::count(), 1);
+//!     <dyn Backend>::for_each_implementor(|name, constructor| {
+//!         let backend: Option<Box<dyn Backend>> = constructor.map(|new| new());
+//!         println!("{}: {}", name, backend.is_some());
+//!     });
+//! }
+//! ```
+//!
+//! Not supported on generic traits.
+//!
+//! ### `serde`
+//!
+//! Requires the `serde` feature. Implements `Serialize` for `dyn T` and
+//! `Deserialize` for `Box<dyn T>` over the closed set of `implementors(...)`,
+//! tagging each value with the name of its implementor. The tag may be tuned
+//! with `#[serde(...)]` variant attributes on the entries. Every implementor
+//! must implement `Serialize` and `Deserialize`, and the crate defining the
+//! trait must depend on `serde`.
+//!
+//! ```rust,ignore
+//! #[sealed(serde, implementors(Ping, #[serde(rename = "pong")] Pong))]
+//! pub trait Message {}
+//!
+//! let messages: Vec<Box<dyn Message>> = vec![Box::new(Ping { sequence: 1 }), Box::new(Pong)];
+//! let json = serde_json::to_string(&messages)?; // [{"Ping":{"sequence":1}},{"pong":null}]
+//! let messages: Vec<Box<dyn Message>> = serde_json::from_str(&json)?;
+//! ```
+//!
+//! ### `ids`
+//!
+//! Requires every implementor to be given a numeric ID with
+//! `#[sealed(id = ...)]` on its impl, which must be unique across the
+//! implementors, e.g. for tagging values in binary protocols. A duplicate or
+//! missing ID fails to compile. Requires `implementors(...)`.
+//!
+//! The ID of a type is available as its associated `ID` constant, and
+//! through `<dyn T>::id_of::<X>()` next to the registry of
+//! `implementors(...)`. The constant is only generated for types named by a
+//! plain identifier, other than the primitive and prelude types, as inherent
+//! impls are limited to local types; a type given IDs for several traits
+//! cannot have it either, as the constants would collide.
+//!
+//! The ID of a value is available through its `id()` method, and
+//! `<dyn T>::from_id` builds a value of the implementor with a given ID with
+//! the constructor given to it in `implementors(...)`, while
+//! `<dyn T>::name_of_id` returns its name. When the trait is not object safe,
+//! `id_of` and `name_of_id` live on `{Trait}Implementors` instead.
+//!
+//! ```rust
+//! # use sealed::sealed;
+//! #
+//! #[sealed(ids, implementors(Ping = Ping::default, Pong))]
+//! pub trait Message {}
+//!
+//! #[derive(Default)]
+//! pub struct Ping;
+//! #[sealed(id = 1)]
+//! impl Message for Ping {}
+//!
+//! pub struct Pong;
+//! #[sealed(id = Ping::ID + 1)]
+//! impl Message for Pong {}
+//!
+//! fn main() {
+//!     assert_eq!(Pong::ID, 2);
+//!     let message: Box<dyn Message> = Box::new(Ping);
+//!     assert_eq!(message.id(), 1);
+//!     assert_eq!(<dyn Message>::from_id(1).unwrap().id(), 1);
+//!     assert_eq!(<dyn Message>::name_of_id(2), Some("Pong"));
+//! }
+//! ```
+//!
+//! ### `by = OtherTrait`
+//!
+//! Reuses the seal of another `#[sealed]` trait instead of generating a new
+//! one, for families of traits sharing the same implementors. Every type
+//! sealed for `OtherTrait` may implement the trait with a plain impl, without
+//! `#[sealed]`, while any other type is rejected. When `OtherTrait` lives in
+//! another module, its sealing module needs to be visible, e.g. with
+//! `pub(crate)`. Cannot be combined with other arguments.
+//!
+//! ```rust
+//! # use sealed::sealed;
+//! #
+//! #[sealed]
+//! pub trait Stream {}
+//!
+//! #[sealed(by = Stream)]
+//! pub trait Readable {}
+//!
+//! pub struct File;
+//! #[sealed]
+//! impl Stream for File {}
+//! impl Readable for File {}
+//! # fn main() {}
+//! ```
+//!
+//! ### `friends("crate-name", ...)`
+//!
+//! Allows the named crates, e.g. other crates of the same workspace, to
+//! implement the trait with `#[sealed(friend)]` on their impls. The seal is
+//! exposed to every friend under a hidden path, named after the friend and
+//! the version of this crate, which `#[sealed(friend)]` derives from the
+//! `CARGO_CRATE_NAME` of the crate being compiled.
+//!
+//! ```rust,ignore
+//! // in `foo-core`
+//! #[sealed(friends("foo-backends"))]
+//! pub trait Backend {}
+//!
+//! // in `foo-backends`
+//! pub struct Postgres;
+//! #[sealed(friend)]
+//! impl foo_core::Backend for Postgres {}
+//! ```
+//!
+//! Note that this is a **soft seal**: the hidden path is public, so any crate
+//! may still implement the trait by naming it, or by being built under the
+//! name of a friend. It keeps honest users out, but does not guarantee the
+//! set of implementors the way the default seal does.
+//!
+//! ### `unsealed_if = <cfg>`
+//!
+//! Unseals the trait when the given `cfg` predicate holds, e.g.
+//! `unsealed_if = feature = "unstable-extend"`, so it may be implemented
+//! downstream for prototyping. The `#[sealed]` impls keep compiling either
+//! way, and the sealed trait is unchanged when the predicate does not hold.
+//! The docs of the trait get a note about it.
+//!
+//! ```rust
+//! # use sealed::sealed;
+//! #
+//! #[sealed(unsealed_if = feature = "unstable-extend")]
+//! pub trait Extension {}
+//! # fn main() {}
+//! ```
+//!
+//! Cannot be combined with `dyn`, `implementors`, `serde` or `ids`, which rely
+//! on the seal.
+//!
+//! ### `escape_hatch`
+//!
+//! Exposes the seal to impls marked with `#[sealed(escape_hatch)]`, which
+//! have to be written `unsafe impl`. So downstream code may still implement
+//! the trait, e.g. for FFI types, through an explicit and greppable opt-in
+//! accepting any breakage:
+//!
+//! ```rust
+//! mod ffi {
+//!     # use sealed::sealed;
+//!     #[sealed(escape_hatch)]
+//!     pub trait Handle {}
+//! }
+//!
+//! struct Socket;
+//!
+//! // I accept breakage.
+//! #[sealed::sealed(escape_hatch)]
+//! unsafe impl ffi::Handle for Socket {}
+//! ```
+//!
+//! The `unsafe` only marks the opt-in, and is removed from the impl. As the
+//! seal is implemented by each of these impls rather than by a blanket impl,
+//! other impls are rejected with the usual error. The seal is exposed under a
+//! hidden path next to the trait, so the trait has to be named by a path
+//! through its module, as for `friend`. Not supported on generic or `unsafe`
+//! traits, or together with `implementors(...)`.
+//!
+//! ### `derive`
+//!
+//! Makes the trait implementable downstream only through the derive macros of
+//! its crate, which check the invariants of the type, like `bytemuck` or
+//! `zerocopy` do. The seal is exposed under the hidden
+//! `__seal_{trait}_derive_v{major}_{minor}` module next to the trait, which
+//! `#[sealed(derived)]` impls use. So a derive macro emits:
+//!
+//! ```rust,ignore
+//! #[::sealed::sealed(derived)]
+//! impl ::my_crate::Pod for #ty {}
+//! ```
+//!
+//! Note that this is a **soft seal**, as for `friends(...)`: hand-written
+//! impls still fail to compile, but outside of the crate the compiler names
+//! the hidden module in its error, as it is the public path to the seal. So
+//! it keeps honest users out, but does not guarantee that every impl comes
+//! from the derive. Cannot be combined with `implementors(...)`.
+//!
+//! ### `extend_macro = name`
+//!
+//! Allows one intended shape of impl outside of the crate: a newtype
+//! forwarding every item of the trait to an existing implementor in one of
+//! its fields. The generated `#[macro_export]`ed `name!` macro expands to
+//! such an impl, together with the seal reached through the hidden
+//! `__seal_{trait}_extend_v{major}_{minor}` module:
+//!
+//! ```rust,ignore
+//! // in `my_crate`
+//! #[sealed(extend_macro = impl_backend)]
+//! pub trait Backend {
+//!     fn connect(&self) -> String;
+//! }
+//!
+//! // downstream
+//! pub struct Logged {
+//!     inner: my_crate::Postgres,
+//! }
+//! my_crate::impl_backend!(Logged => inner: my_crate::Postgres);
+//! ```
+//!
+//! When the trait is not at the crate root, its module is given with
+//! `extend_macro = name in crate::some::path`. The items of the trait may not
+//! mention `Self` other than through their receiver or associated items, and
+//! the trait may not be generic. Cannot be combined with `implementors(...)`.
+//!
+//! Note that this is a **soft seal**, as for `friends(...)`: the hidden module
+//! is public, and the compiler names it in the error of any other downstream
+//! impl. So it keeps honest users to the macro, but does not guarantee that
+//! every impl outside of the crate forwards to an implementor.
+//!
+//! ### `doctest`
+//!
+//! Appends hidden doctests to the docs of the trait, proving it is sealed: a
+//! `compile_fail` one implementing the trait for a fresh type outside of the
+//! crate, so `cargo test --doc` fails if the seal is ever widened, and one
+//! declaring the same items in a local trait, so a wrong path or an
+//! unresolved type is noticed even where the error code of the former is not
+//! checked. Both import everything from the module of the trait, which is the
+//! crate root unless given with `doctest in crate::some::path`, so the types
+//! of the signatures have to be reachable from there.
+//!
+//! ```rust
+//! # use sealed::sealed;
+//! #
+//! #[sealed(doctest)]
+//! pub trait Backend {
+//!     fn connect(&self) -> String;
+//! }
+//! # fn main() {}
+//! ```
+//!
+//! The fresh type is a unit struct, implementing the required items of the
+//! trait with `unimplemented!()` and `()` associated types. Any bound it did
+//! not satisfy would fail the doctest with the same error as the seal, so
+//! traits with supertraits, `where Self: ...` bounds or bounded associated
+//! types are rejected, as are generic traits.
+//!
+//! ### `mock`
+//!
+//! Generates a [`mockall`](https://docs.rs/mockall) mock of the trait with
+//! `#[mockall::automock]`, and implements the seal for it, so the `Mock{Trait}`
+//! type may be used by the tests of the crate and, when its `mock` feature is
+//! enabled, by the tests of downstream crates. Production builds are as sealed
+//! as before. The crate has to depend on `mockall`, both as a dev-dependency
+//! and as an optional dependency enabled by its `mock` feature.
+//!
+//! ```toml
+//! [dependencies]
+//! mockall = { version = "0.11", optional = true }
+//!
+//! [dev-dependencies]
+//! mockall = "0.11"
+//!
+//! [features]
+//! mock = ["mockall"]
+//! ```
+//!
+//! ```rust,ignore
+//! #[sealed(mock)]
+//! pub trait Engine {
+//!     fn thrust(&self) -> f32;
+//! }
+//!
+//! // in the tests of a downstream crate, enabling the `mock` feature
+//! let mut engine = MockEngine::new();
+//! engine.expect_thrust().return_const(2.0);
+//! ```
+//!
+//! As `automock` does not implement supertraits, the trait should have none
+//! besides the seal. Cannot be combined with `dyn` or `implementors(...)`.
+//!
 //! ### `pub(crate)` or `pub(in some::path)`
 //!
 //! Allows to tune visibility of the generated sealing module (the default one
@@ -117,198 +683,805 @@
 //! impl T for A {}
 //! ```
 
-use std::fmt;
-
 use proc_macro::TokenStream;
 use proc_macro2::{Span, TokenStream as TokenStream2};
 use quote::{format_ident, quote};
+use sealed_core::{
+    implementors_path_for, seal_impl, seal_impl_header, seal_trait, snake_ident, ImplHeader,
+    ImplOptions, Options,
+};
 use syn::{
     ext::IdentExt,
-    parse::{Parse, ParseStream},
-    parse_macro_input, parse_quote,
-    spanned::Spanned,
-    token,
+    parse::{Parse, ParseStream, Parser as _},
+    parse_macro_input, parse_quote, token,
 };
 
 #[proc_macro_attribute]
 pub fn sealed(args: TokenStream, input: TokenStream) -> TokenStream {
+    // Impls are only parsed up to their body, falling back to parsing a full
+    // item for traits, and for malformed impls to report the `syn` error.
+    if let Ok(header) = syn::parse::<ImplHeader>(input.clone()) {
+        return seal_impl_header(&header, parse_macro_input!(args))
+            .unwrap_or_else(|e| e.to_compile_error())
+            .into();
+    }
     match parse_macro_input!(input) {
-        syn::Item::Impl(item_impl) => parse_sealed_impl(&item_impl),
-        syn::Item::Trait(item_trait) => {
-            Ok(parse_sealed_trait(item_trait, parse_macro_input!(args)))
-        }
+        syn::Item::Impl(item_impl) => seal_impl(&item_impl, parse_macro_input!(args)),
+        syn::Item::Trait(item_trait) => seal_trait(item_trait, parse_macro_input!(args)),
         _ => Err(syn::Error::new(Span::call_site(), "expected impl or trait")),
     }
     .unwrap_or_else(|e| e.to_compile_error())
     .into()
 }
 
-// Care for https://gist.github.com/Kestrer/8c05ebd4e0e9347eb05f265dfb7252e1#procedural-macros-support-renaming-the-crate
-fn parse_sealed_trait(mut item_trait: syn::ItemTrait, args: TraitArguments) -> TokenStream2 {
-    let trait_ident = &item_trait.ident.unraw();
-    let trait_generics = &item_trait.generics;
-    let trait_supertraits = &item_trait.supertraits;
-    let seal = seal_name(trait_ident);
-    let vis = &args.visibility;
-
-    let (_, ty_generics, where_clause) = trait_generics.split_for_impl();
-
-    let mod_code = if args.erased {
-        let lifetimes = trait_generics.lifetimes();
-        let const_params = trait_generics.const_params();
-        let type_params =
-            trait_generics
-                .type_params()
-                .map(|syn::TypeParam { ident, .. }| -> syn::TypeParam {
-                    parse_quote!( #ident : ?Sized )
-                });
+#[proc_macro_attribute]
+pub fn test_each(args: TokenStream, input: TokenStream) -> TokenStream {
+    parse_test_each(parse_macro_input!(args), parse_macro_input!(input))
+        .unwrap_or_else(|e| e.to_compile_error())
+        .into()
+}
 
-        quote! {
-            pub trait Sealed< #(#lifetimes ,)* #(#type_params ,)* #(#const_params ,)* > {}
+#[proc_macro_attribute]
+pub fn builder(args: TokenStream, input: TokenStream) -> TokenStream {
+    let _ = parse_macro_input!(args as syn::parse::Nothing);
+    parse_builder(parse_macro_input!(input))
+        .unwrap_or_else(|e| e.to_compile_error())
+        .into()
+}
+
+#[proc_macro_attribute]
+pub fn type_enum(args: TokenStream, input: TokenStream) -> TokenStream {
+    parse_type_enum(parse_macro_input!(args), parse_macro_input!(input))
+        .unwrap_or_else(|e| e.to_compile_error())
+        .into()
+}
+
+#[proc_macro_attribute]
+pub fn open(args: TokenStream, input: TokenStream) -> TokenStream {
+    let _ = parse_macro_input!(args as syn::parse::Nothing);
+    let item_trait = parse_macro_input!(input as syn::ItemTrait);
+    quote!(#item_trait).into()
+}
+
+#[proc_macro]
+pub fn assert_sealed(input: TokenStream) -> TokenStream {
+    parse_assert_sealed(parse_macro_input!(input))
+        .unwrap_or_else(|e| e.to_compile_error())
+        .into()
+}
+
+#[proc_macro]
+pub fn assert_implementors(input: TokenStream) -> TokenStream {
+    parse_assert_implementors(parse_macro_input!(input))
+        .unwrap_or_else(|e| e.to_compile_error())
+        .into()
+}
+
+#[proc_macro]
+pub fn typestate(input: TokenStream) -> TokenStream {
+    parse_typestate(parse_macro_input!(input))
+        .unwrap_or_else(|e| e.to_compile_error())
+        .into()
+}
+
+fn parse_test_each(trait_path: syn::Path, item_fn: syn::ItemFn) -> syn::Result<TokenStream2> {
+    let sig = &item_fn.sig;
+    if !sig.inputs.is_empty() || sig.generics.type_params().count() != 1 {
+        return Err(syn::Error::new_spanned(
+            sig,
+            "expected a function without arguments and with exactly one type \
+             parameter, e.g. `fn check<T: Trait>()`",
+        ));
+    }
+
+    let fn_ident = &sig.ident;
+    let output = &sig.output;
+    let callback = format_ident!("__sealed_test_each_{}", fn_ident.unraw());
+
+    // The tests are placed in a module named after the function, which glob
+    // imports its parent. So only paths relative to `self` or `super` need to
+    // climb one more level to resolve the implementors.
+    let implementors_path = implementors_path_for(&trait_path)?;
+    let mut types_prefix = trait_path;
+    types_prefix.segments.pop();
+    if let Some(first) = types_prefix.segments.first() {
+        if first.ident == "self" {
+            types_prefix.segments[0] = parse_quote!(super);
+        } else if first.ident == "super" {
+            types_prefix.segments.insert(0, parse_quote!(super));
+        }
+    }
+    let types_prefix = types_prefix.segments.iter();
+
+    Ok(quote! {
+        #item_fn
+
+        macro_rules! #callback {
+            ($( $(#[$meta:meta])* $name:ident => $implementor:ident ),*) => {
+                mod #fn_ident {
+                    #[allow(unused_imports)]
+                    use super::*;
+
+                    $(
+                        $(#[$meta])*
+                        #[test]
+                        fn $name() #output {
+                            super::#fn_ident::<#(#types_prefix::)* $implementor>()
+                        }
+                    )*
+                }
+            };
+        }
+        #implementors_path! { #callback! {} }
+    })
+}
+
+fn parse_assert_sealed(trait_path: syn::Path) -> syn::Result<TokenStream2> {
+    let trait_str = quote!(#trait_path).to_string().replace(' ', "");
+    let name = trait_str.replace("::", "_");
+    let source = format!(
+        "// Generated by `sealed::assert_sealed!`, checking that `{0}` cannot\n\
+         // be implemented outside of its crate.\n\
+         \n\
+         struct Fresh;\n\
+         \n\
+         impl {0} for Fresh {{}}\n\
+         \n\
+         fn main() {{}}\n",
+        trait_str,
+    );
+
+    // The case is written next to the integration tests, so its expected
+    // output may be reviewed and committed like any `trybuild` one.
+    Ok(quote! {{
+        let path = ::std::path::Path::new(::core::env!("CARGO_MANIFEST_DIR"))
+            .join("tests")
+            .join("sealed")
+            .join(::core::concat!(#name, ".rs"));
+        let source = #source;
+        if ::std::fs::read_to_string(&path).ok().as_deref() != ::core::option::Option::Some(source) {
+            ::std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            ::std::fs::write(&path, source).unwrap();
         }
-    } else {
-        // `trait_generics` does not output its where clause when tokenized (due
-        // to supertraits in the middle). So we output them separately.
+        ::trybuild::TestCases::new().compile_fail(path);
+    }})
+}
+
+fn parse_assert_implementors(input: AssertImplementors) -> syn::Result<TokenStream2> {
+    let AssertImplementors { trait_path, types } = input;
+    let trait_str = quote!(#trait_path).to_string().replace(' ', "");
+    let names = types
+        .iter()
+        .map(|ty| {
+            let ident = &ty.segments.last().unwrap().ident.unraw();
+            syn::LitStr::new(&ident.to_string(), ident.span())
+        })
+        .collect::<Vec<_>>();
+
+    // The registry is read from the holder type next to the trait, which is
+    // generated whether the trait is object safe or not.
+    let mut registry = trait_path.clone();
+    let last = registry.segments.last_mut().unwrap();
+    last.ident = format_ident!("{}Implementors", last.ident.unraw());
+    last.arguments = syn::PathArguments::None;
+
+    Ok(quote! {{
+        fn assert_implementor<X: ?::core::marker::Sized + #trait_path>() {}
+        #( assert_implementor::<#types>(); )*
+
+        let mut expected: ::std::vec::Vec<&str> = ::std::vec![#(#names),*];
+        expected.sort_unstable();
+        let mut actual = #registry::IMPLEMENTORS.to_vec();
+        actual.sort_unstable();
+        ::core::assert_eq!(actual, expected, "implementors of `{}`", #trait_str);
+    }})
+}
+
+fn parse_typestate(typestate: Typestate) -> syn::Result<TokenStream2> {
+    let Typestate {
+        mut item_struct,
+        transitions,
+    } = typestate;
+
+    if !item_struct.generics.params.is_empty() {
+        return Err(syn::Error::new_spanned(
+            &item_struct.generics,
+            "generic parameters are not supported, as the state is the only one",
+        ));
+    }
+    if transitions.is_empty() {
+        return Err(syn::Error::new_spanned(
+            &item_struct.ident,
+            "expected at least one transition, e.g. `Idle -> Running: start`",
+        ));
+    }
+
+    let vis = &item_struct.vis;
+    let struct_ident = &item_struct.ident;
+    let trait_ident = format_ident!("{}State", struct_ident.unraw());
+
+    // States are declared in the order of their first appearance.
+    let mut states = Vec::<&syn::Ident>::new();
+    for t in &transitions {
+        for state in [&t.from, &t.to] {
+            if !states.contains(&state) {
+                states.push(state);
+            }
+        }
+    }
+
+    let item_trait: syn::ItemTrait = {
+        let doc = format!("States of [`{}`].", struct_ident.unraw());
+        parse_quote! {
+            #[doc = #doc]
+            #vis trait #trait_ident {}
+        }
+    };
+    let args: Options = parse_quote!(implementors(#(#states),*));
+    let sealed_trait = seal_trait(item_trait, args)?;
+
+    let markers = states
+        .iter()
+        .map(|state| {
+            let doc = format!("`{}` state of [`{}`].", state.unraw(), struct_ident.unraw());
+            let item_impl = parse_quote!(impl #trait_ident for #state {});
+            let sealed_impl = seal_impl(&item_impl, ImplOptions::default())?;
+            Ok(quote! {
+                #[doc = #doc]
+                #[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+                #vis struct #state;
+                #sealed_impl
+            })
+        })
+        .collect::<syn::Result<Vec<_>>>()?;
+
+    let field_idents = item_struct
+        .fields
+        .iter()
+        .map(|field| field.ident.clone())
+        .collect::<Option<Vec<_>>>()
+        .ok_or_else(|| {
+            syn::Error::new_spanned(
+                &item_struct.fields,
+                "expected named fields or a unit struct",
+            )
+        })?;
+    let field_types = item_struct
+        .fields
+        .iter()
+        .map(|field| field.ty.clone())
+        .collect::<Vec<_>>();
+    let state_field: syn::Field =
+        syn::Field::parse_named.parse2(quote!(state: ::core::marker::PhantomData<State>))?;
+    item_struct.fields = match item_struct.fields {
+        syn::Fields::Named(mut fields) => {
+            fields.named.push(state_field);
+            syn::Fields::Named(fields)
+        }
+        _ => syn::Fields::Named(parse_quote!({ #state_field })),
+    };
+    item_struct.semi_token = None;
+    item_struct.generics = parse_quote!(<State: #trait_ident>);
+
+    // The struct starts in the first state, which is the source of the first
+    // transition.
+    let initial = states[0];
+    let constructor = {
+        let doc = format!(
+            "Creates a [`{}`] in its initial `{}` state.",
+            struct_ident.unraw(),
+            initial.unraw(),
+        );
         quote! {
-            use super::*;
-            pub trait Sealed #trait_generics : #trait_supertraits #where_clause {}
+            impl #struct_ident<#initial> {
+                #[doc = #doc]
+                #[must_use]
+                #vis fn new(#(#field_idents: #field_types),*) -> Self {
+                    Self {
+                        #(#field_idents,)*
+                        state: ::core::marker::PhantomData,
+                    }
+                }
+            }
         }
     };
 
-    item_trait
-        .supertraits
-        .push(parse_quote!( #seal::Sealed #ty_generics ));
-    quote! {
-        #[automatically_derived]
-        #vis mod #seal {
-            #mod_code
+    let transitions = transitions
+        .iter()
+        .map(|t| {
+            let Transition {
+                attrs,
+                from,
+                to,
+                method,
+                args,
+            } = t;
+            // Arguments set the fields of the same name, the others are moved.
+            if let Some((arg, _)) = args.iter().find(|(arg, _)| !field_idents.contains(arg)) {
+                return Err(syn::Error::new_spanned(
+                    arg,
+                    format!(
+                        "expected the name of a field of `{}`, as the arguments \
+                         of a transition set the fields of the same name",
+                        struct_ident.unraw(),
+                    ),
+                ));
+            }
+            let arg_idents = args.iter().map(|(arg, _)| arg);
+            let arg_types = args.iter().map(|(_, ty)| ty);
+            let moved = field_idents
+                .iter()
+                .filter(|field| !args.iter().any(|(arg, _)| arg == *field));
+            Ok(quote! {
+                impl #struct_ident<#from> {
+                    #(#attrs)*
+                    #[must_use]
+                    #vis fn #method(self, #(#arg_idents: #arg_types),*) -> #struct_ident<#to> {
+                        let Self { #(#moved,)* .. } = self;
+                        #struct_ident {
+                            #(#field_idents,)*
+                            state: ::core::marker::PhantomData,
+                        }
+                    }
+                }
+            })
+        })
+        .collect::<syn::Result<Vec<_>>>()?;
+
+    Ok(quote! {
+        #sealed_trait
+        #(#markers)*
+        #item_struct
+        #constructor
+        #(#transitions)*
+    })
+}
+
+fn parse_builder(mut item_struct: syn::ItemStruct) -> syn::Result<TokenStream2> {
+    if !item_struct.generics.params.is_empty() {
+        return Err(syn::Error::new_spanned(
+            &item_struct.generics,
+            "generic parameters are not supported",
+        ));
+    }
+    let fields = match &mut item_struct.fields {
+        syn::Fields::Named(fields) => &mut fields.named,
+        fields => {
+            return Err(syn::Error::new_spanned(fields, "expected named fields"));
         }
-        #item_trait
+    };
+
+    let mut builder_fields = Vec::with_capacity(fields.len());
+    for field in fields.iter_mut() {
+        let mut default = None;
+        for attr in field
+            .attrs
+            .iter()
+            .filter(|attr| attr.path().is_ident("builder"))
+        {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("default") {
+                    default = Some(if meta.input.peek(token::Eq) {
+                        meta.value()?.parse()?
+                    } else {
+                        parse_quote!(::core::default::Default::default())
+                    });
+                    Ok(())
+                } else {
+                    Err(meta.error("unknown `builder` attribute argument, expected `default`"))
+                }
+            })?;
+        }
+        field.attrs.retain(|attr| !attr.path().is_ident("builder"));
+        builder_fields.push(BuilderField {
+            field: field.clone(),
+            default,
+        });
     }
-}
 
-fn parse_sealed_impl(item_impl: &syn::ItemImpl) -> syn::Result<TokenStream2> {
-    let impl_trait = item_impl
-        .trait_
-        .as_ref()
-        .ok_or_else(|| syn::Error::new_spanned(item_impl, "missing implementation trait"))?;
+    let vis = &item_struct.vis;
+    let struct_ident = &item_struct.ident;
+    let builder_ident = format_ident!("{}Builder", struct_ident.unraw());
+    let module = format_ident!("{}_builder", snake_ident(struct_ident).unraw());
 
-    let mut sealed_path = impl_trait.1.segments.clone();
+    let item_trait: syn::ItemTrait = parse_quote! {
+        /// State of a required field, either [`Set`] or [`Unset`].
+        pub trait FieldState {}
+    };
+    let args: Options = parse_quote!(implementors(Set, Unset));
+    let sealed_trait = seal_trait(item_trait, args)?;
+    let sealed_impls = [parse_quote!(Set), parse_quote!(Unset)]
+        .iter()
+        .map(|marker: &syn::Ident| {
+            seal_impl(
+                &parse_quote!(impl FieldState for #marker {}),
+                ImplOptions::default(),
+            )
+        })
+        .collect::<syn::Result<Vec<_>>>()?;
 
-    // since `impl for ...` is not allowed, this path will *always* have at least length 1
-    // thus both `first` and `last` are safe to unwrap
-    let syn::PathSegment { ident, arguments } = sealed_path.pop().unwrap().into_value();
-    let seal = seal_name(ident.unraw());
-    sealed_path.push(parse_quote!( #seal ));
-    sealed_path.push(parse_quote!(Sealed));
+    let field_idents = builder_fields
+        .iter()
+        .map(|f| f.field.ident.as_ref().unwrap())
+        .collect::<Vec<_>>();
+    let field_types = builder_fields
+        .iter()
+        .map(|f| &f.field.ty)
+        .collect::<Vec<_>>();
+    let required = builder_fields
+        .iter()
+        .filter(|f| f.default.is_none())
+        .collect::<Vec<_>>();
+    // Numbered rather than named after the fields, as distinct fields such as
+    // `a_b` and `a__b` would share a name, while avoiding to shadow their types.
+    let states = (0..required.len())
+        .map(|i| format_ident!("__State{}", i))
+        .collect::<Vec<_>>();
 
-    let self_type = &item_impl.self_ty;
+    let setters = builder_fields.iter().map(|f| {
+        let ident = f.field.ident.as_ref().unwrap();
+        let ty = &f.field.ty;
+        let docs = f
+            .field
+            .attrs
+            .iter()
+            .filter(|attr| attr.path().is_ident("doc"));
+        if f.default.is_some() {
+            return quote! {
+                impl<#(#states: #module::FieldState),*> #builder_ident<#(#states),*> {
+                    #(#docs)*
+                    #[must_use]
+                    #vis fn #ident(mut self, #ident: #ty) -> Self {
+                        self.#ident = ::core::option::Option::Some(#ident);
+                        self
+                    }
+                }
+            };
+        }
+        let position = required
+            .iter()
+            .position(|r| r.field.ident.as_ref() == Some(ident))
+            .unwrap();
+        let other_states = states
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| *i != position)
+            .map(|(_, state)| state);
+        let from_states = states.iter().enumerate().map(|(i, state)| {
+            if i == position {
+                quote!(#module::Unset)
+            } else {
+                quote!(#state)
+            }
+        });
+        let to_states = states.iter().enumerate().map(|(i, state)| {
+            if i == position {
+                quote!(#module::Set)
+            } else {
+                quote!(#state)
+            }
+        });
+        let other_fields = field_idents.iter().filter(|&&other| other != ident);
+        quote! {
+            impl<#(#other_states: #module::FieldState),*> #builder_ident<#(#from_states),*> {
+                #(#docs)*
+                #[must_use]
+                #vis fn #ident(self, #ident: #ty) -> #builder_ident<#(#to_states),*> {
+                    #builder_ident {
+                        #ident: ::core::option::Option::Some(#ident),
+                        #( #other_fields: self.#other_fields, )*
+                        __state: ::core::marker::PhantomData,
+                    }
+                }
+            }
+        }
+    });
 
-    // Only keep the introduced params (no bounds), since
-    // the bounds may break in the `#seal` submodule.
-    let (trait_generics, _, where_clauses) = item_impl.generics.split_for_impl();
+    let builds = builder_fields.iter().map(|f| {
+        let ident = f.field.ident.as_ref().unwrap();
+        match &f.default {
+            Some(default) => quote!(self.#ident.unwrap_or_else(|| #default)),
+            None => quote! {
+                match self.#ident {
+                    ::core::option::Option::Some(value) => value,
+                    ::core::option::Option::None => {
+                        ::core::unreachable!("required fields are set, as tracked by their state")
+                    }
+                }
+            },
+        }
+    });
+    let set_states = states.iter().map(|_| quote!(#module::Set));
+    let unset_states = states.iter().map(|_| quote!(#module::Unset));
+
+    let module_doc = format!(
+        "Markers tracking the required fields of [`{}`].",
+        builder_ident,
+    );
+    let builder_doc = format!(
+        "Builder of [`{}`], tracking whether its required fields are set.",
+        struct_ident.unraw(),
+    );
 
     Ok(quote! {
-        #[automatically_derived]
-        impl #trait_generics #sealed_path #arguments for #self_type #where_clauses {}
-        #item_impl
+        #item_struct
+
+        #[doc = #module_doc]
+        #vis mod #module {
+            #sealed_trait
+
+            /// State of a required field which was set.
+            #[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+            pub struct Set;
+
+            /// State of a required field which was not set yet.
+            #[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+            pub struct Unset;
+
+            #(#sealed_impls)*
+        }
+
+        #[doc = #builder_doc]
+        #[must_use]
+        #vis struct #builder_ident<#(#states: #module::FieldState = #module::Unset),*> {
+            #( #field_idents: ::core::option::Option<#field_types>, )*
+            __state: ::core::marker::PhantomData<(#(#states,)*)>,
+        }
+
+        impl #struct_ident {
+            /// Returns a builder with all the fields unset.
+            #vis fn builder() -> #builder_ident<#(#unset_states),*> {
+                #builder_ident {
+                    #( #field_idents: ::core::option::Option::None, )*
+                    __state: ::core::marker::PhantomData,
+                }
+            }
+        }
+
+        #(#setters)*
+
+        impl #builder_ident<#(#set_states),*> {
+            /// Builds the value, once all the required fields are set.
+            #vis fn build(self) -> #struct_ident {
+                #struct_ident {
+                    #( #field_idents: #builds, )*
+                }
+            }
+        }
     })
 }
 
-/// Convert a string into snake case.
-///
-/// Stolen't from <https://github.com/jmg-duarte/sealed-rs/pull/6#pullrequestreview-653837118>
-fn to_snake_case(s: &'_ str) -> String {
-    let mut ret = String::with_capacity(s.len());
-    let mut first = true;
-    s.bytes().for_each(|c| {
-        if c.is_ascii_uppercase() {
-            if !first {
-                ret.push('_');
+fn parse_type_enum(args: TypeEnumArguments, item_enum: syn::ItemEnum) -> syn::Result<TokenStream2> {
+    if !item_enum.generics.params.is_empty() {
+        return Err(syn::Error::new_spanned(
+            &item_enum.generics,
+            "generic parameters are not supported",
+        ));
+    }
+    if let Some(variant) = item_enum
+        .variants
+        .iter()
+        .find(|v| !matches!(v.fields, syn::Fields::Unit))
+    {
+        return Err(syn::Error::new_spanned(
+            &variant.fields,
+            "expected unit variants only, as every variant becomes a marker type",
+        ));
+    }
+
+    let vis = &item_enum.vis;
+    let enum_ident = &item_enum.ident;
+    let trait_ident = format_ident!("{}Kind", enum_ident.unraw());
+    let dispatch = format_ident!("dispatch_{}", snake_ident(enum_ident).unraw());
+    let path = args.path.map(|path| quote!(#path::));
+
+    let variants = &item_enum.variants;
+    let idents = variants.iter().map(|v| &v.ident).collect::<Vec<_>>();
+    let cfgs = variants
+        .iter()
+        .map(|v| {
+            v.attrs
+                .iter()
+                .filter(|attr| attr.path().is_ident("cfg"))
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+
+    let item_trait: syn::ItemTrait = {
+        let doc = format!(
+            "Marker types of the variants of [`{}`].",
+            enum_ident.unraw()
+        );
+        parse_quote! {
+            #[doc = #doc]
+            #vis trait #trait_ident {
+                /// Variant represented by this marker type.
+                const VALUE: #enum_ident;
             }
-            ret.push(c.to_ascii_lowercase() as char);
-        } else {
-            ret.push(c as char);
         }
-        first = false;
+    };
+    let implementors = variants.iter().map(|v| {
+        let ident = &v.ident;
+        let cfgs = v.attrs.iter().filter(|attr| attr.path().is_ident("cfg"));
+        quote!(#(#cfgs)* #ident)
     });
-    ret
-}
+    let args: Options = parse_quote!(implementors(#(#implementors),*));
+    let sealed_trait = seal_trait(item_trait, args)?;
+
+    let markers = variants
+        .iter()
+        .map(|v| {
+            let ident = &v.ident;
+            let attrs = v
+                .attrs
+                .iter()
+                .filter(|attr| attr.path().is_ident("cfg") || attr.path().is_ident("doc"));
+            let cfgs = v.attrs.iter().filter(|attr| attr.path().is_ident("cfg"));
+            let item_impl = parse_quote! {
+                impl #trait_ident for #ident {
+                    const VALUE: #enum_ident = #enum_ident::#ident;
+                }
+            };
+            let sealed_impl = seal_impl(&item_impl, ImplOptions::default())?;
+            Ok(quote! {
+                #(#attrs)*
+                #[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+                #vis struct #ident;
+                #(#cfgs)*
+                const _: () = {
+                    #sealed_impl
+                };
+            })
+        })
+        .collect::<syn::Result<Vec<_>>>()?;
+
+    let dispatch_doc = format!(
+        "Calls the given expression with the marker type of the [`{}`] value \
+         bound to the given name, e.g. `{}!(value, |K| f::<K>())`.",
+        enum_ident.unraw(),
+        dispatch,
+    );
 
-/// Constructs [`syn::Ident`] of a sealing module name.
-fn seal_name<D: fmt::Display>(seal: D) -> syn::Ident {
-    format_ident!("__seal_{}", to_snake_case(&seal.to_string()))
+    Ok(quote! {
+        #item_enum
+        #sealed_trait
+        #(#markers)*
+
+        #[doc = #dispatch_doc]
+        #[allow(unused_macros)]
+        macro_rules! #dispatch {
+            ($value:expr, |$kind:ident| $body:expr $(,)?) => {
+                match $value {
+                    #(
+                        #(#cfgs)*
+                        #path #enum_ident::#idents => {
+                            #[allow(dead_code)]
+                            type $kind = #path #idents;
+                            $body
+                        }
+                    )*
+                }
+            };
+        }
+        #[allow(unused_imports)]
+        pub(crate) use #dispatch;
+    })
 }
 
-/// Arguments accepted by `#[sealed]` attribute when placed on a trait
-/// definition.
-struct TraitArguments {
-    /// `erase` argument indicating whether trait bounds erasure should be used.
-    ///
-    /// Default is `false`.
-    erased: bool,
+/// Field of a `#[sealed::builder]` struct.
+struct BuilderField {
+    field: syn::Field,
+
+    /// Default value given with `#[builder(default)]` or
+    /// `#[builder(default = ...)]`, making the field optional.
+    default: Option<syn::Expr>,
+}
 
-    /// `pub` argument defining visibility of the generated sealing module.
+/// Arguments accepted by `#[sealed::type_enum]` attribute.
+#[derive(Default)]
+struct TypeEnumArguments {
+    /// `in some::path` argument giving the path of the module of the enum,
+    /// so the generated `dispatch_*!` macro may be used anywhere in the crate.
     ///
-    /// Default is [`syn::Visibility::Inherited`].
-    visibility: syn::Visibility,
+    /// Default is [`None`], resolving the enum and its markers where the
+    /// macro is used.
+    path: Option<syn::Path>,
 }
 
-impl Default for TraitArguments {
-    fn default() -> Self {
-        Self {
-            erased: false,
-            visibility: syn::Visibility::Inherited,
+impl Parse for TypeEnumArguments {
+    fn parse(input: ParseStream<'_>) -> syn::Result<Self> {
+        let mut out = Self::default();
+        if !input.is_empty() {
+            input.parse::<token::In>()?;
+            out.path = Some(input.call(syn::Path::parse_mod_style)?);
         }
+        Ok(out)
     }
 }
 
-impl Parse for TraitArguments {
+/// Input of the `assert_implementors!` macro, e.g. `my_crate::T: A, B`.
+struct AssertImplementors {
+    trait_path: syn::Path,
+    types: Vec<syn::Path>,
+}
+
+impl Parse for AssertImplementors {
     fn parse(input: ParseStream<'_>) -> syn::Result<Self> {
-        let mut out = Self::default();
+        let trait_path = input.parse()?;
+        input.parse::<token::Colon>()?;
+        let types = input
+            .parse_terminated(syn::Path::parse, token::Comma)?
+            .into_iter()
+            .collect();
+        Ok(Self { trait_path, types })
+    }
+}
 
-        while !input.is_empty() {
-            let ident = syn::Ident::parse_any(&input.fork())?;
+/// Input of the `typestate!` macro: a struct definition followed by the
+/// transitions between its states.
+struct Typestate {
+    item_struct: syn::ItemStruct,
+    transitions: Vec<Transition>,
+}
 
-            match ident.to_string().as_str() {
-                "erase" => {
-                    syn::Ident::parse_any(input)?;
-                    out.erased = true;
-                }
+impl Parse for Typestate {
+    fn parse(input: ParseStream<'_>) -> syn::Result<Self> {
+        Ok(Self {
+            item_struct: input.parse()?,
+            transitions: input
+                .parse_terminated(Transition::parse, token::Comma)?
+                .into_iter()
+                .collect(),
+        })
+    }
+}
 
-                "pub" => {
-                    out.visibility = input.parse()?;
-                    if matches!(out.visibility, syn::Visibility::Public(_)) {
-                        return Err(syn::Error::new(
-                            out.visibility.span(),
-                            "`pub` visibility breaks the seal as allows to use \
-                             it outside its crate.\n\
-                             Consider tightening the visibility (e.g. \
-                             `pub(crate)`) if you actually need sealing.",
-                        ));
-                    }
-                }
+/// Transition of the `typestate!` macro, e.g. `Idle -> Running: start` or
+/// `Idle -> Running: start(speed: f32)`.
+struct Transition {
+    /// Attributes of the generated method, e.g. its docs.
+    attrs: Vec<syn::Attribute>,
 
-                unknown => {
-                    return Err(syn::Error::new(
-                        ident.span(),
-                        format!("unknown `{}` attribute argument", unknown),
-                    ))
-                }
-            }
+    from: syn::Ident,
+    to: syn::Ident,
+    method: syn::Ident,
 
-            if input
-                .lookahead1()
-                .peek(token::Comma)
-                .then(|| input.parse::<token::Comma>())
-                .transpose()?
-                .is_none()
-                && !input.is_empty()
-            {
-                return Err(syn::Error::new(ident.span(), "expected followed by `,`"));
-            }
-        }
+    /// Arguments of the generated method, each setting the field of the same
+    /// name.
+    args: Vec<(syn::Ident, syn::Type)>,
+}
 
-        Ok(out)
+impl Parse for Transition {
+    fn parse(input: ParseStream<'_>) -> syn::Result<Self> {
+        let attrs = input.call(syn::Attribute::parse_outer)?;
+        let from = input.parse()?;
+        input.parse::<token::RArrow>()?;
+        let to = input.parse()?;
+        input.parse::<token::Colon>()?;
+        let method = input.parse()?;
+        let args = if input.peek(token::Paren) {
+            let content;
+            syn::parenthesized!(content in input);
+            content
+                .parse_terminated(
+                    |input| {
+                        let arg = input.parse()?;
+                        input.parse::<token::Colon>()?;
+                        Ok((arg, input.parse()?))
+                    },
+                    token::Comma,
+                )?
+                .into_iter()
+                .collect()
+        } else {
+            Vec::new()
+        };
+        Ok(Self {
+            attrs,
+            from,
+            to,
+            method,
+            args,
+        })
     }
 }
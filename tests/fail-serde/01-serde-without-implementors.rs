@@ -0,0 +1,9 @@
+use sealed::sealed;
+
+#[sealed(serde)]
+pub trait T {}
+
+#[sealed(implementors(#[serde(rename = "a")] A))]
+pub trait U {}
+
+fn main() {}
@@ -0,0 +1,11 @@
+use sealed::sealed;
+
+#[sealed(dyn(Clone))]
+pub trait T {}
+
+pub struct A;
+
+#[sealed]
+impl T for A {}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use sealed::sealed;
+
+#[sealed(erase, dyn(Clone))]
+pub trait T {}
+
+#[sealed(dyn(Ord))]
+pub trait U {}
+
+fn main() {}
@@ -0,0 +1,16 @@
+use sealed::sealed;
+
+#[sealed(implementors(A))]
+pub trait T {}
+
+pub struct A;
+
+#[sealed]
+impl T for A {}
+
+pub struct B;
+
+#[sealed]
+impl T for B {}
+
+fn main() {}
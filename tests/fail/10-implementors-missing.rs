@@ -0,0 +1,13 @@
+use sealed::sealed;
+
+#[sealed(implementors(A, B))]
+pub trait T {}
+
+pub struct A;
+
+#[sealed]
+impl T for A {}
+
+pub struct B;
+
+fn main() {}
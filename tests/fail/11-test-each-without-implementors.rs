@@ -0,0 +1,17 @@
+use sealed::sealed;
+
+#[sealed]
+pub trait T {}
+
+pub struct A;
+
+#[sealed]
+impl T for A {}
+
+#[sealed::test_each(T)]
+fn check<X: T>() {}
+
+#[sealed::test_each(T)]
+fn check_value<X: T>(_value: X) {}
+
+fn main() {}
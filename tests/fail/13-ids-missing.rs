@@ -0,0 +1,14 @@
+use sealed::sealed;
+
+#[sealed(ids, implementors(Ping, Pong))]
+pub trait Message {}
+
+pub struct Ping;
+#[sealed(id = 7)]
+impl Message for Ping {}
+
+pub struct Pong;
+#[sealed]
+impl Message for Pong {}
+
+fn main() {}
@@ -0,0 +1,13 @@
+sealed::typestate! {
+    pub struct Door;
+
+    Closed -> Open: open,
+    Open -> Closed: close,
+}
+
+fn main() {
+    let door = Door::<Closed> {
+        state: std::marker::PhantomData,
+    };
+    let _ = door.close();
+}
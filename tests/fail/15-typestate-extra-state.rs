@@ -0,0 +1,13 @@
+sealed::typestate! {
+    pub struct Door;
+
+    Closed -> Open: open,
+    Open -> Closed: close,
+}
+
+pub struct Ajar;
+
+#[sealed::sealed]
+impl DoorState for Ajar {}
+
+fn main() {}
@@ -0,0 +1,14 @@
+#[sealed::type_enum]
+pub enum Color {
+    Red,
+    Green,
+}
+
+pub struct Blue;
+
+#[sealed::sealed]
+impl ColorKind for Blue {
+    const VALUE: Color = Color::Red;
+}
+
+fn main() {}
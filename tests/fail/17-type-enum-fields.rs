@@ -0,0 +1,7 @@
+#[sealed::type_enum]
+pub enum Color {
+    Red,
+    Rgb(u8, u8, u8),
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+#[sealed::builder]
+pub struct Request {
+    pub url: String,
+    pub method: String,
+}
+
+fn main() {
+    let _ = Request::builder().url("localhost".to_owned()).build();
+}
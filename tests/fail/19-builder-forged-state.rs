@@ -0,0 +1,11 @@
+#[sealed::builder]
+pub struct Request {
+    pub url: String,
+}
+
+pub struct Forged;
+
+#[sealed::sealed]
+impl request_builder::FieldState for Forged {}
+
+fn main() {}
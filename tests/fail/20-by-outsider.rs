@@ -0,0 +1,17 @@
+use sealed::sealed;
+
+#[sealed]
+pub trait Stream {}
+
+#[sealed(by = Stream)]
+pub trait Readable {}
+
+pub struct File;
+#[sealed]
+impl Stream for File {}
+impl Readable for File {}
+
+pub struct Socket;
+impl Readable for Socket {}
+
+fn main() {}
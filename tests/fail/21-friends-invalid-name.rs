@@ -0,0 +1,6 @@
+use sealed::sealed;
+
+#[sealed(friends("foo-backends", "not a crate"))]
+pub trait T {}
+
+fn main() {}
@@ -0,0 +1,10 @@
+use sealed::sealed;
+
+#[sealed(unsealed_if = feature = "unstable-extend")]
+pub trait T {}
+
+pub struct A;
+
+impl T for A {}
+
+fn main() {}
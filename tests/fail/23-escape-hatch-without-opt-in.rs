@@ -0,0 +1,12 @@
+mod ffi {
+    use sealed::sealed;
+
+    #[sealed(escape_hatch)]
+    pub trait Handle {}
+}
+
+struct Socket;
+
+impl ffi::Handle for Socket {}
+
+fn main() {}
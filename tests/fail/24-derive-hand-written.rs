@@ -0,0 +1,12 @@
+mod pod {
+    use sealed::sealed;
+
+    #[sealed(derive)]
+    pub trait Pod {}
+}
+
+struct Point;
+
+impl pod::Pod for Point {}
+
+fn main() {}
@@ -0,0 +1,16 @@
+use sealed::sealed;
+
+#[sealed(extend_macro = impl_backend)]
+pub trait Backend {
+    fn connect(&self) -> String;
+}
+
+pub struct Postgres;
+
+impl Backend for Postgres {
+    fn connect(&self) -> String {
+        "postgres://localhost".to_owned()
+    }
+}
+
+fn main() {}
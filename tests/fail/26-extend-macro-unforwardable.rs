@@ -0,0 +1,8 @@
+use sealed::sealed;
+
+#[sealed(extend_macro = impl_backend)]
+pub trait Backend {
+    fn new() -> Self;
+}
+
+fn main() {}
@@ -0,0 +1,8 @@
+use sealed::sealed;
+
+#[sealed(mock, implementors(Postgres))]
+pub trait Backend {}
+
+pub struct Postgres;
+
+fn main() {}
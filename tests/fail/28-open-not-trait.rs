@@ -0,0 +1,4 @@
+#[sealed::open]
+pub struct A;
+
+fn main() {}
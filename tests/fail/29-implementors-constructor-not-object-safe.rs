@@ -0,0 +1,16 @@
+use sealed::sealed;
+
+#[sealed(implementors(Json = Json::default))]
+pub trait Format {
+    type Output;
+}
+
+#[derive(Default)]
+pub struct Json;
+
+#[sealed]
+impl Format for Json {
+    type Output = String;
+}
+
+fn main() {}
@@ -0,0 +1,18 @@
+use sealed::sealed;
+
+#[sealed(doctest)]
+pub trait Backend: Clone {}
+
+#[sealed(doctest)]
+pub trait Frame
+where
+    Self: std::fmt::Debug,
+{
+}
+
+#[sealed(doctest)]
+pub trait Format {
+    type Output: Default;
+}
+
+fn main() {}
@@ -0,0 +1,13 @@
+mod ffi {
+    use sealed::sealed;
+
+    #[sealed(escape_hatch)]
+    pub trait Handle {}
+}
+
+struct Socket;
+
+#[sealed::sealed(escape_hatch)]
+impl ffi::Handle for Socket {}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use sealed::sealed;
+
+#[sealed]
+pub trait Stream {}
+
+#[sealed(mock, by = Stream)]
+pub trait Readable {}
+
+fn main() {}
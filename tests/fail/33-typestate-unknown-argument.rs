@@ -0,0 +1,10 @@
+sealed::typestate! {
+    pub struct Door {
+        angle: f32,
+    }
+
+    Closed -> Open: open(speed: f32),
+    Open -> Closed: close,
+}
+
+fn main() {}
@@ -0,0 +1,20 @@
+mod backends {
+    use sealed::sealed;
+
+    #[sealed(implementors(Postgres))]
+    pub trait Backend {}
+
+    pub struct Postgres;
+
+    #[sealed]
+    impl Backend for Postgres {}
+}
+
+mod backend_tests {
+    use crate::backends::Backend;
+
+    #[sealed::test_each(Backend)]
+    fn sized<X: Backend>() {}
+}
+
+fn main() {}
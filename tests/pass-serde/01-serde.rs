@@ -0,0 +1,43 @@
+use sealed::sealed;
+use serde::{Deserialize, Serialize};
+
+#[sealed(serde, implementors(Ping, #[serde(rename = "pong", alias = "Pong")] Pong, #[cfg(any())] Quit))]
+pub trait Message {
+    fn describe(&self) -> String;
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct Ping {
+    sequence: u32,
+}
+
+#[sealed]
+impl Message for Ping {
+    fn describe(&self) -> String {
+        format!("ping #{}", self.sequence)
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct Pong;
+
+#[sealed]
+impl Message for Pong {
+    fn describe(&self) -> String {
+        "pong".to_owned()
+    }
+}
+
+fn main() {
+    let messages: Vec<Box<dyn Message>> = vec![Box::new(Ping { sequence: 1 }), Box::new(Pong)];
+
+    let json = serde_json::to_string(&messages).unwrap();
+    assert_eq!(json, r#"[{"Ping":{"sequence":1}},{"pong":null}]"#);
+
+    let messages: Vec<Box<dyn Message>> =
+        serde_json::from_str(r#"[{"Pong":null},{"Ping":{"sequence":2}}]"#).unwrap();
+    let descriptions = messages.iter().map(|m| m.describe()).collect::<Vec<_>>();
+    assert_eq!(descriptions, ["pong", "ping #2"]);
+
+    assert!(serde_json::from_str::<Box<dyn Message>>(r#"{"Quit":null}"#).is_err());
+}
@@ -0,0 +1,46 @@
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+use sealed::sealed;
+
+#[sealed(dyn(Clone, PartialEq, Hash, Debug))]
+pub trait Shape {
+    fn area(&self) -> u32;
+}
+
+#[derive(Clone, PartialEq, Hash, Debug)]
+pub struct Square(u32);
+
+#[sealed]
+impl Shape for Square {
+    fn area(&self) -> u32 {
+        self.0 * self.0
+    }
+}
+
+#[derive(Clone, PartialEq, Hash, Debug)]
+pub struct Rectangle(u32, u32);
+
+#[sealed]
+impl Shape for Rectangle {
+    fn area(&self) -> u32 {
+        self.0 * self.1
+    }
+}
+
+fn hash(shape: &Box<dyn Shape>) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    shape.hash(&mut hasher);
+    hasher.finish()
+}
+
+fn main() {
+    let square: Box<dyn Shape> = Box::new(Square(2));
+    let rectangle: Box<dyn Shape> = Box::new(Rectangle(2, 2));
+
+    assert_eq!(square.clone().area(), 4);
+    assert!(square == square.clone());
+    assert!(*square != *rectangle);
+    assert_eq!(hash(&square), hash(&square.clone()));
+    assert_eq!(format!("{:?}", rectangle), "Rectangle(2, 2)");
+}
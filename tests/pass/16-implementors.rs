@@ -0,0 +1,94 @@
+use sealed::sealed;
+
+#[sealed(implementors(Postgres = Postgres::default, Sqlite, #[cfg(any())] Oracle))]
+pub trait Backend {
+    fn name(&self) -> &'static str;
+}
+
+#[derive(Default)]
+pub struct Postgres;
+
+#[sealed]
+impl Backend for Postgres {
+    fn name(&self) -> &'static str {
+        "postgres"
+    }
+}
+
+pub struct Sqlite;
+
+#[sealed]
+impl Backend for Sqlite {
+    fn name(&self) -> &'static str {
+        "sqlite"
+    }
+}
+
+#[cfg(any())]
+pub struct Oracle;
+
+#[cfg(any())]
+#[sealed]
+impl Backend for Oracle {
+    fn name(&self) -> &'static str {
+        "oracle"
+    }
+}
+
+#[sealed(implementors(Json, #[cfg(any())] Yaml))]
+pub trait Format {
+    type Output;
+}
+
+pub struct Json;
+
+#[sealed]
+impl Format for Json {
+    type Output = String;
+}
+
+#[sealed(implementors(Square))]
+pub trait Shape: Clone + Send + std::fmt::Debug {}
+
+#[derive(Clone, Debug)]
+pub struct Square;
+
+#[sealed]
+impl Shape for Square {}
+
+#[sealed(implementors(Circle))]
+pub trait Area: std::fmt::Debug + Sync {
+    fn area(&self) -> f64;
+}
+
+#[derive(Debug)]
+pub struct Circle;
+
+#[sealed]
+impl Area for Circle {
+    fn area(&self) -> f64 {
+        3.14
+    }
+}
+
+const COUNT: usize = <dyn Backend>::count();
+
+fn main() {
+    assert_eq!(<dyn Backend>::IMPLEMENTORS, &["Postgres", "Sqlite"]);
+    assert_eq!(COUNT, 2);
+
+    let mut names = Vec::new();
+    <dyn Backend>::for_each_implementor(|name, constructor| {
+        if let Some(constructor) = constructor {
+            names.push(constructor().name());
+        } else {
+            names.push(name);
+        }
+    });
+    assert_eq!(names, ["postgres", "Sqlite"]);
+
+    assert_eq!(FormatImplementors::IMPLEMENTORS, &["Json"]);
+    assert_eq!(FormatImplementors::count(), 1);
+    assert_eq!(ShapeImplementors::IMPLEMENTORS, &["Square"]);
+    assert_eq!(<dyn Area>::IMPLEMENTORS, &["Circle"]);
+}
@@ -0,0 +1,70 @@
+use sealed::sealed;
+
+#[sealed(implementors(A, B))]
+pub trait T {
+    fn value() -> u32;
+}
+
+pub struct A;
+
+#[sealed]
+impl T for A {
+    fn value() -> u32 {
+        1
+    }
+}
+
+pub struct B;
+
+#[sealed]
+impl T for B {
+    fn value() -> u32 {
+        2
+    }
+}
+
+mod tests {
+    use super::*;
+
+    #[sealed::test_each(T)]
+    fn positive<X: T>() {
+        assert!(X::value() > 0);
+    }
+
+    mod nested {
+        #[sealed::test_each(super::super::T)]
+        fn positive<X: super::super::T>() {
+            assert!(X::value() > 0);
+        }
+    }
+}
+
+mod unrelated {
+    // Not a descendant of the module of the trait, whose sealing module is
+    // private to it.
+    #[sealed::test_each(crate::T)]
+    fn positive<X: crate::T>() {
+        assert!(X::value() > 0);
+    }
+}
+
+mod backends {
+    use sealed::sealed;
+
+    #[sealed(implementors(Postgres))]
+    pub trait Backend {}
+
+    pub struct Postgres;
+
+    #[sealed]
+    impl Backend for Postgres {}
+}
+
+mod backend_tests {
+    #[sealed::test_each(crate::backends::Backend)]
+    fn sized<X: crate::backends::Backend>() {
+        assert_eq!(std::mem::size_of::<X>(), 0);
+    }
+}
+
+fn main() {}
@@ -0,0 +1,50 @@
+use sealed::sealed;
+
+#[sealed(ids, implementors(Ping = Ping::default, Pong, #[cfg(any())] Quit))]
+pub trait Message {}
+
+#[derive(Default)]
+pub struct Ping;
+#[sealed(id = 7)]
+impl Message for Ping {}
+
+pub struct Pong;
+#[sealed(id = Ping::ID + 1)]
+impl Message for Pong {}
+
+#[cfg(any())]
+pub struct Quit;
+#[cfg(any())]
+#[sealed(id = 7)]
+impl Message for Quit {}
+
+#[sealed(ids, implementors(u8))]
+pub trait Opcode {
+    const WIDTH: usize;
+}
+
+#[sealed(id = 1)]
+impl Opcode for u8 {
+    const WIDTH: usize = 1;
+}
+
+fn main() {
+    assert_eq!(Ping::ID, 7);
+    assert_eq!(Pong::ID, 8);
+    assert_eq!(<dyn Message>::id_of::<Ping>(), 7);
+    assert_eq!(<dyn Message>::id_of::<Pong>(), 8);
+
+    let messages: Vec<Box<dyn Message>> = vec![Box::new(Ping), Box::new(Pong)];
+    let ids = messages.iter().map(|m| m.id()).collect::<Vec<_>>();
+    assert_eq!(ids, [7, 8]);
+
+    assert_eq!(<dyn Message>::from_id(7).map(|m| m.id()), Some(7));
+    assert!(<dyn Message>::from_id(8).is_none());
+    assert!(<dyn Message>::from_id(9).is_none());
+    assert_eq!(<dyn Message>::name_of_id(7), Some("Ping"));
+    assert_eq!(<dyn Message>::name_of_id(8), Some("Pong"));
+    assert_eq!(<dyn Message>::name_of_id(9), None);
+
+    assert_eq!(OpcodeImplementors::id_of::<u8>(), 1);
+    assert_eq!(OpcodeImplementors::name_of_id(1), Some("u8"));
+}
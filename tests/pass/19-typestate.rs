@@ -0,0 +1,54 @@
+sealed::typestate! {
+    /// A connection, generic over its state.
+    #[derive(Debug)]
+    pub struct Connection {
+        pub address: String,
+        retries: u8,
+    }
+
+    Closed -> Open: open,
+    /// Starts the handshake.
+    Open -> Handshaking: handshake,
+    Handshaking -> Established: establish,
+    Handshaking -> Closed: abort(retries: u8),
+    Established -> Closed: close,
+    Closed -> Closed: redirect(address: String, retries: u8,),
+}
+
+sealed::typestate! {
+    struct Light;
+
+    Off -> On: switch_on,
+    On -> Off: switch_off(),
+}
+
+fn is_state<S: ConnectionState>(_: &Connection<S>) {}
+
+fn main() {
+    let connection: Connection<Closed> = Connection::new("localhost".to_owned(), 3);
+    let connection = connection
+        .open()
+        .handshake()
+        .abort(2)
+        .open()
+        .handshake()
+        .establish();
+    is_state(&connection);
+    assert_eq!(connection.retries, 2);
+    let connection: Connection<Closed> = connection.close();
+    assert_eq!(connection.address, "localhost");
+    let connection = connection.redirect("example.com".to_owned(), 5);
+    assert_eq!(connection.address, "example.com");
+    assert_eq!(connection.retries, 5);
+
+    let explicit = Connection::<Closed> {
+        address: "localhost".to_owned(),
+        retries: 3,
+        state: std::marker::PhantomData,
+    };
+    let _: Connection<Open> = explicit.open();
+
+    let light: Light<Off> = Light::new();
+    let _: Light<Off> = light.switch_on().switch_off();
+    assert_eq!(<dyn LightState>::IMPLEMENTORS, ["Off", "On"]);
+}
@@ -0,0 +1,49 @@
+mod colors {
+    #[sealed::type_enum(in crate::colors)]
+    #[derive(Clone, Copy, Debug, PartialEq)]
+    pub enum Color {
+        /// The red one.
+        Red,
+        Green,
+        #[cfg(any())]
+        Blue,
+    }
+}
+
+use colors::{Color, ColorKind};
+
+#[sealed::type_enum]
+#[derive(Debug, PartialEq)]
+enum Shape {
+    Circle,
+    Square = 4,
+}
+
+fn name<C: ColorKind>() -> &'static str {
+    std::any::type_name::<C>()
+}
+
+fn value<C: ColorKind>() -> Color {
+    C::VALUE
+}
+
+fn main() {
+    assert_eq!(colors::Red::VALUE, Color::Red);
+    assert_eq!(<colors::Green as ColorKind>::VALUE, Color::Green);
+
+    for color in [Color::Red, Color::Green] {
+        assert_eq!(colors::dispatch_color!(color, |C| value::<C>()), color);
+    }
+    let color = Color::Green;
+    assert!(colors::dispatch_color!(color, |C| name::<C>()).ends_with("Green"));
+
+    let sides = |shape: &Shape| {
+        dispatch_shape!(*shape, |S| match S::VALUE {
+            Shape::Circle => 0,
+            Shape::Square => 4,
+        })
+    };
+    assert_eq!(sides(&Shape::Square), 4);
+    assert_eq!(Square::VALUE as u8, 4);
+    assert_eq!(Circle, Circle::default());
+}
@@ -0,0 +1,74 @@
+#![allow(non_snake_case)]
+
+mod http {
+    #[sealed::builder]
+    #[derive(Debug, PartialEq)]
+    pub struct Request {
+        /// Where to send the request.
+        pub url: String,
+        pub header_count: u8,
+        #[builder(default)]
+        pub timeout: Option<u32>,
+        #[builder(default = "GET".to_owned())]
+        pub method: String,
+    }
+}
+
+use http::{request_builder::Unset, Request, RequestBuilder};
+
+#[sealed::builder]
+struct Empty {
+    #[builder(default = 1)]
+    value: u8,
+}
+
+// Fields whose names would collide once camel cased, or are not ASCII.
+#[sealed::builder]
+struct Names {
+    foo_bar: u8,
+    foo__bar: u8,
+    _foo: u8,
+    foo: u8,
+    état: u8,
+}
+
+#[sealed::builder]
+struct Été {
+    jour: u8,
+}
+
+fn main() {
+    let request = Request::builder()
+        .timeout(Some(30))
+        .header_count(2)
+        .url("localhost".to_owned())
+        .build();
+    assert_eq!(
+        request,
+        Request {
+            url: "localhost".to_owned(),
+            header_count: 2,
+            timeout: Some(30),
+            method: "GET".to_owned(),
+        }
+    );
+
+    let builder: RequestBuilder = Request::builder();
+    let _: RequestBuilder<Unset, Unset> = builder.method("POST".to_owned());
+
+    assert_eq!(Empty::builder().build().value, 1);
+    assert_eq!(Empty::builder().value(2).build().value, 2);
+
+    let names = Names::builder()
+        .foo_bar(1)
+        .foo__bar(2)
+        ._foo(3)
+        .foo(4)
+        .état(5)
+        .build();
+    assert_eq!(
+        [names.foo_bar, names.foo__bar, names._foo, names.foo, names.état],
+        [1, 2, 3, 4, 5]
+    );
+    assert_eq!(Été::builder().jour(21).build().jour, 21);
+}
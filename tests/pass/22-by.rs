@@ -0,0 +1,41 @@
+mod io {
+    use sealed::sealed;
+
+    #[sealed(pub(crate))]
+    pub trait Stream {}
+
+    #[sealed(by = Stream)]
+    pub trait Readable: Stream {
+        fn read(&self) -> u8;
+    }
+
+    pub struct File;
+    #[sealed]
+    impl Stream for File {}
+    impl Readable for File {
+        fn read(&self) -> u8 {
+            1
+        }
+    }
+
+    #[sealed]
+    pub trait Generic<T> {}
+
+    #[sealed(by = Generic<T>)]
+    pub trait GenericWritable<T> {}
+
+    #[sealed]
+    impl Generic<u8> for File {}
+    impl GenericWritable<u8> for File {}
+}
+
+#[sealed::sealed(by = io::Stream)]
+pub trait Writable {}
+
+impl Writable for io::File {}
+
+fn main() {
+    use io::Readable;
+
+    assert_eq!(io::File.read(), 1);
+}
@@ -0,0 +1,26 @@
+use sealed::sealed;
+
+#[sealed(unsealed_if = all())]
+pub trait Unsealed {}
+
+#[sealed(erase, unsealed_if = any(feature = "never", all()))]
+pub trait UnsealedGeneric<T> {}
+
+#[sealed(unsealed_if = any())]
+pub trait Sealed {}
+
+pub struct A;
+
+#[sealed]
+impl Unsealed for A {}
+#[sealed]
+impl UnsealedGeneric<u8> for A {}
+#[sealed]
+impl Sealed for A {}
+
+pub struct B;
+
+impl Unsealed for B {}
+impl UnsealedGeneric<u8> for B {}
+
+fn main() {}
@@ -0,0 +1,51 @@
+mod ffi {
+    use sealed::sealed;
+
+    #[sealed(escape_hatch)]
+    pub trait Handle: Send {
+        fn raw(&self) -> usize;
+    }
+
+    #[sealed(escape_hatch, erase)]
+    pub trait Erased {}
+
+    #[sealed(escape_hatch, dyn(Clone))]
+    pub trait Cloneable {}
+
+    pub struct File;
+
+    #[sealed]
+    impl Handle for File {
+        fn raw(&self) -> usize {
+            3
+        }
+    }
+}
+
+use ffi::Handle as _;
+use sealed::sealed;
+
+#[derive(Clone)]
+struct Socket;
+
+// I accept breakage.
+#[sealed(escape_hatch)]
+unsafe impl ffi::Handle for Socket {
+    fn raw(&self) -> usize {
+        4
+    }
+}
+
+#[sealed(escape_hatch)]
+unsafe impl ffi::Erased for Socket {}
+
+#[sealed(escape_hatch)]
+unsafe impl ffi::Cloneable for Socket {}
+
+fn main() {
+    assert_eq!(ffi::File.raw(), 3);
+    assert_eq!(Socket.raw(), 4);
+
+    let socket: Box<dyn ffi::Cloneable> = Box::new(Socket);
+    let _ = socket.clone();
+}
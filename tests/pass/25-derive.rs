@@ -0,0 +1,35 @@
+mod pod {
+    use sealed::sealed;
+
+    /// Plain old data, only implementable with `derive_pod!`.
+    #[sealed(derive)]
+    pub trait Pod: Copy {}
+
+    #[sealed]
+    impl Pod for u8 {}
+
+    // Stands for the derive macro of a companion crate, which checks the
+    // invariants of the type before emitting the impl.
+    #[macro_export]
+    macro_rules! derive_pod {
+        ($ty:ty) => {
+            #[sealed::sealed(derived)]
+            impl $crate::pod::Pod for $ty {}
+        };
+    }
+}
+
+#[derive(Clone, Copy)]
+struct Point {
+    _x: u8,
+    _y: u8,
+}
+
+derive_pod!(Point);
+
+fn assert_pod<T: pod::Pod>() {}
+
+fn main() {
+    assert_pod::<u8>();
+    assert_pod::<Point>();
+}
@@ -0,0 +1,69 @@
+mod db {
+    use sealed::sealed;
+
+    #[sealed(extend_macro = impl_backend in crate::db)]
+    pub trait Backend {
+        type Connection;
+        const PORT: u16;
+
+        fn connect(&self, retries: u8) -> Self::Connection;
+        fn name(&self) -> String {
+            "backend".to_owned()
+        }
+        fn reset(&mut self);
+        fn into_name(self) -> String
+        where
+            Self: Sized;
+        fn parse<T: std::str::FromStr>(&self, input: &str) -> Option<T>;
+    }
+
+    pub struct Postgres {
+        pub resets: u8,
+    }
+
+    #[sealed]
+    impl Backend for Postgres {
+        type Connection = String;
+        const PORT: u16 = 5432;
+
+        fn connect(&self, retries: u8) -> String {
+            format!("postgres://localhost ({} retries)", retries)
+        }
+        fn name(&self) -> String {
+            "postgres".to_owned()
+        }
+        fn reset(&mut self) {
+            self.resets += 1;
+        }
+        fn into_name(self) -> String {
+            self.name()
+        }
+        fn parse<T: std::str::FromStr>(&self, input: &str) -> Option<T> {
+            input.parse().ok()
+        }
+    }
+}
+
+use db::{Backend, Postgres};
+
+pub struct Logged {
+    pub inner: Postgres,
+}
+
+impl_backend!(Logged => inner: Postgres);
+
+pub struct Wrapped(Postgres);
+
+impl_backend!(Wrapped => 0: Postgres);
+
+fn main() {
+    let mut logged = Logged {
+        inner: Postgres { resets: 0 },
+    };
+    assert_eq!(logged.connect(2), "postgres://localhost (2 retries)");
+    logged.reset();
+    assert_eq!(logged.inner.resets, 1);
+    assert_eq!(logged.parse::<u8>("7"), Some(7));
+    assert_eq!(<Logged as Backend>::PORT, 5432);
+    assert_eq!(Wrapped(Postgres { resets: 0 }).into_name(), "postgres");
+}
@@ -0,0 +1,27 @@
+#![deny(missing_docs, rustdoc::broken_intra_doc_links)]
+//! Sealed traits get a `Sealed` section in their docs.
+
+use sealed::sealed;
+
+/// A custom wording.
+#[sealed(doc = "Only `A` may implement this trait.")]
+pub trait Custom {}
+
+/// Implementors are listed.
+#[sealed(implementors(A, #[cfg(any())] B))]
+pub trait Listed {}
+
+/// Shares the seal of `Listed`.
+#[sealed(by = Listed, doc = "Implemented by the implementors of `Listed`.")]
+pub trait Shared {}
+
+/// The only implementor.
+pub struct A;
+
+#[sealed]
+impl Custom for A {}
+#[sealed]
+impl Listed for A {}
+impl Shared for A {}
+
+fn main() {}
@@ -0,0 +1,53 @@
+use async_trait::async_trait;
+use sealed::sealed;
+
+#[sealed(extend_macro = impl_outer)]
+#[async_trait]
+pub trait Outer {
+    async fn get(&self) -> u8;
+}
+
+#[async_trait]
+#[sealed(extend_macro = impl_inner)]
+pub trait Inner {
+    async fn get(&self) -> u8;
+}
+
+#[sealed]
+#[async_trait(?Send)]
+pub trait Local {
+    async fn get(&self) -> u8;
+}
+
+pub struct A;
+
+#[sealed]
+#[async_trait]
+impl Outer for A {
+    async fn get(&self) -> u8 {
+        1
+    }
+}
+
+#[async_trait]
+#[sealed]
+impl Inner for A {
+    async fn get(&self) -> u8 {
+        2
+    }
+}
+
+#[sealed]
+#[async_trait(?Send)]
+impl Local for A {
+    async fn get(&self) -> u8 {
+        3
+    }
+}
+
+pub struct Wrapper(A);
+
+impl_outer!(Wrapper => 0: A);
+impl_inner!(Wrapper => 0: A);
+
+fn main() {}
@@ -0,0 +1,19 @@
+//! Open traits are left unchanged.
+
+/// Implemented downstream.
+#[sealed::open]
+pub trait Plugin {
+    fn name(&self) -> &str;
+}
+
+pub struct A;
+
+impl Plugin for A {
+    fn name(&self) -> &str {
+        "a"
+    }
+}
+
+fn main() {
+    assert_eq!(A.name(), "a");
+}
@@ -0,0 +1,110 @@
+//! Checks that the traits of `demo-core` are recognizable as sealed in its
+//! rustdoc JSON, as semver tooling relies on it to classify changes.
+//!
+//! The JSON output is unstable, so the test is ignored unless run on nightly
+//! with `cargo +nightly test --test rustdoc_json -- --ignored`.
+
+use std::{collections::HashSet, path::Path, process::Command};
+
+use serde_json::Value;
+
+#[test]
+#[ignore = "requires a nightly toolchain"]
+fn sealed_in_rustdoc_json() {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let target_dir = root.join("target").join("rustdoc-json");
+    let status = Command::new(env!("CARGO"))
+        .current_dir(root)
+        .args([
+            "rustdoc",
+            "--quiet",
+            "--package",
+            "demo-core",
+            "--target-dir",
+        ])
+        .arg(&target_dir)
+        .args(["--", "-Z", "unstable-options", "--output-format", "json"])
+        .status()
+        .expect("failed to run `cargo rustdoc`");
+    assert!(status.success(), "`cargo rustdoc` failed");
+
+    let json = std::fs::read_to_string(target_dir.join("doc").join("demo_core.json"))
+        .expect("failed to read the rustdoc JSON");
+    let krate: Value = serde_json::from_str(&json).expect("invalid rustdoc JSON");
+
+    let public = public_items(&krate);
+    for (name, seal) in [
+        ("Engine", "__seal_engine"),
+        ("Sensor", "__seal_sensor"),
+        ("Frame", "__seal_frame"),
+        ("Payload", "__seal_payload"),
+    ] {
+        let item = krate["index"]
+            .as_object()
+            .unwrap()
+            .values()
+            .find(|item| item["name"] == name)
+            .unwrap_or_else(|| panic!("`{}` is missing from the rustdoc JSON", name));
+        let mut supertraits = Vec::new();
+        resolved_paths(&item["inner"], &mut supertraits);
+        let (_, id) = supertraits
+            .iter()
+            .find(|(path, _)| path == &format!("{}::Sealed", seal))
+            .unwrap_or_else(|| panic!("`{}` is not bound by `{}::Sealed`", name, seal));
+        assert!(
+            !public.contains(id),
+            "`{}::Sealed` of `{}` is part of the public API",
+            seal,
+            name,
+        );
+    }
+}
+
+/// Collects the ids of the items reachable from the crate root through public
+/// modules and re-exports, which are the only ones semver tooling considers
+/// nameable outside of the crate. Hidden items are left out of the JSON.
+fn public_items(krate: &Value) -> HashSet<String> {
+    let index = &krate["index"];
+    let mut public = HashSet::new();
+    let mut pending = vec![id(&krate["root"])];
+    while let Some(item_id) = pending.pop() {
+        if !public.insert(item_id.clone()) {
+            continue;
+        }
+        let inner = &index[item_id.as_str()]["inner"];
+        let children = inner["module"]["items"].as_array().into_iter().flatten();
+        // re-exports are `use` items, or `import` ones in older formats
+        let targets = ["use", "import"].iter().map(|kind| &inner[*kind]["id"]);
+        pending.extend(
+            children
+                .chain(targets)
+                .filter(|child| !child.is_null())
+                .map(id)
+                .filter(|child| index[child.as_str()]["visibility"] == "public"),
+        );
+    }
+    public
+}
+
+/// Returns `value` as an id, which is a number or, in older formats, a string.
+fn id(value: &Value) -> String {
+    value.to_string().trim_matches('"').to_owned()
+}
+
+/// Collects the paths and ids of the traits resolved in `value`, which spell
+/// the path as `name` or, in newer formats, as `path`.
+fn resolved_paths(value: &Value, paths: &mut Vec<(String, String)>) {
+    match value {
+        Value::Object(object) => {
+            let path = object.get("path").or_else(|| object.get("name"));
+            if let (Some(Value::String(path)), Some(id)) = (path, object.get("id")) {
+                paths.push((path.clone(), self::id(id)));
+            }
+            object
+                .values()
+                .for_each(|value| resolved_paths(value, paths));
+        }
+        Value::Array(array) => array.iter().for_each(|value| resolved_paths(value, paths)),
+        _ => {}
+    }
+}
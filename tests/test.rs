@@ -4,3 +4,11 @@ fn compile_test() {
     t.pass("tests/pass/*.rs");
     t.compile_fail("tests/fail/*.rs");
 }
+
+#[cfg(feature = "serde")]
+#[test]
+fn compile_test_serde() {
+    let t = trybuild::TestCases::new();
+    t.pass("tests/pass-serde/*.rs");
+    t.compile_fail("tests/fail-serde/*.rs");
+}
@@ -0,0 +1,17 @@
+use sealed::sealed;
+
+#[sealed]
+#[trait_variant::make(Backend: Send)]
+pub trait LocalBackend {
+    async fn connect(&self) -> u8;
+}
+
+pub struct Sqlite;
+
+impl Backend for Sqlite {
+    async fn connect(&self) -> u8 {
+        1
+    }
+}
+
+fn main() {}
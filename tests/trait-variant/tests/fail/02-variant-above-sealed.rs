@@ -0,0 +1,9 @@
+use sealed::sealed;
+
+#[trait_variant::make(Backend: Send)]
+#[sealed]
+pub trait LocalBackend {
+    async fn connect(&self) -> u8;
+}
+
+fn main() {}
@@ -0,0 +1,22 @@
+use sealed::sealed;
+
+#[sealed]
+#[trait_variant::make(Backend: Send)]
+pub trait LocalBackend {
+    async fn connect(&self) -> u8;
+}
+
+pub struct Postgres;
+
+#[sealed]
+impl Backend for Postgres {
+    async fn connect(&self) -> u8 {
+        1
+    }
+}
+
+fn assert_local_backend<T: LocalBackend>() {}
+
+fn main() {
+    assert_local_backend::<Postgres>();
+}
@@ -0,0 +1,31 @@
+use sealed::sealed;
+
+#[sealed]
+#[trait_variant::make(Send)]
+pub trait Outer {
+    async fn connect(&self) -> u8;
+}
+
+#[trait_variant::make(Send)]
+#[sealed]
+pub trait Inner {
+    async fn connect(&self) -> u8;
+}
+
+pub struct Postgres;
+
+#[sealed]
+impl Outer for Postgres {
+    async fn connect(&self) -> u8 {
+        1
+    }
+}
+
+#[sealed]
+impl Inner for Postgres {
+    async fn connect(&self) -> u8 {
+        2
+    }
+}
+
+fn main() {}
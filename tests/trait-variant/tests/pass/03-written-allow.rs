@@ -0,0 +1,39 @@
+use sealed::sealed;
+
+// Only the lint allowed by `#[trait_variant::make]` above `#[sealed]` is
+// rejected, not one written by hand or by a declarative macro.
+#[allow(async_fn_in_trait)]
+#[sealed]
+pub trait LocalBackend {
+    async fn connect(&self) -> u8;
+}
+
+macro_rules! sealed_backend {
+    ($name:ident) => {
+        #[allow(async_fn_in_trait)]
+        #[sealed]
+        pub trait $name {
+            async fn connect(&self) -> u8;
+        }
+    };
+}
+
+sealed_backend!(MacroBackend);
+
+pub struct Postgres;
+
+#[sealed]
+impl LocalBackend for Postgres {
+    async fn connect(&self) -> u8 {
+        1
+    }
+}
+
+#[sealed]
+impl MacroBackend for Postgres {
+    async fn connect(&self) -> u8 {
+        2
+    }
+}
+
+fn main() {}